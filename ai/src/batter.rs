@@ -0,0 +1,116 @@
+use bevy_ecs::{
+    prelude::{Local, Query, Res, ResMut, State, With},
+    query::Has,
+};
+use bevy_transform::prelude::Transform;
+
+use cricket_pong_game::{
+    actions::{Action, Actions, BatterAction},
+    base::{
+        batter::{Bat, Batter},
+        Bot, Identity, PlayerOne, Position,
+    },
+    BallCountdown, GamePhase,
+};
+
+use crate::{
+    difficulty::BotDifficulty,
+    predict::{angle_difference, angle_of, circle_intercept},
+    vision::{BotRng, BotVision},
+};
+
+// how close the bat has to be to where it wants to be before it stops turning
+const AIM_TOLERANCE: f32 = 0.05;
+
+fn turn_towards(actions: &mut Actions, player: Identity, from: f32, to: f32) {
+    let turn = angle_difference(from, to);
+    if turn > AIM_TOLERANCE {
+        actions.push(player, Action::Batter(BatterAction::MoveCCW));
+    } else if turn < -AIM_TOLERANCE {
+        actions.push(player, Action::Batter(BatterAction::MoveCW));
+    }
+}
+
+// waits just clockwise of where the next ball will meet the bat,
+// winds up as it comes in and lets go so that it meets the ball mid-swing
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn play_batter(
+    bot_query: Query<(&Position, Has<PlayerOne>), With<Bot>>,
+    batter_query: Query<(&Batter, &Bat, &Transform)>,
+    vision: Res<BotVision>,
+    phase: Res<State<GamePhase>>,
+    countdown: Res<BallCountdown>,
+    difficulty: Res<BotDifficulty>,
+    mut rng: ResMut<BotRng>,
+    mut actions: ResMut<Actions>,
+    // how early or late the next swing will be let go
+    mut jitter: Local<Option<f32>>,
+) {
+    let Some(player) = bot_query.iter().find_map(|(position, is_player_one)| {
+        (*position == Position::Batter).then_some(Identity::of(is_player_one))
+    }) else {
+        return;
+    };
+    if *phase == GamePhase::Preparing {
+        if countdown.remaining.is_some() {
+            actions.push(player, Action::Batter(BatterAction::Ready));
+        }
+        return;
+    }
+    if *phase != GamePhase::Bowling && *phase != GamePhase::Active {
+        return;
+    }
+    let Ok((batter, bat, transform)) = batter_query.get_single() else { return };
+    if batter.swing_timer.is_some() {
+        return;
+    }
+    let bat_position = transform.translation.truncate();
+    let bat_angle = angle_of(bat_position);
+    let bat_radius = bat_position.length();
+
+    // the ball that will reach the bat soonest
+    let incoming = vision
+        .seen()
+        .iter()
+        .filter_map(|ball| {
+            let reach = bat_radius + bat.hdepth + ball.radius;
+            circle_intercept(ball.position, ball.velocity, reach)
+        })
+        .min_by(|(time, _), (other_time, _)| time.total_cmp(other_time));
+    let Some((time, meeting_point)) = incoming else {
+        if batter.charge.is_some() {
+            // the ball got away, so don't hold the swing forever
+            actions.push(player, Action::Batter(BatterAction::SwingCCW));
+            *jitter = None;
+        } else if let Some(nearest) = vision
+            .seen()
+            .iter()
+            .map(|ball| ball.position)
+            .min_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+        {
+            // nothing is coming yet, so face whoever has the ball
+            turn_towards(&mut actions, player, bat_angle, angle_of(nearest));
+        }
+        return;
+    };
+
+    let swing_lead = bat.swing_time / 2.;
+    let jitter_lead =
+        *jitter.get_or_insert_with(|| rng.0.spread(difficulty.profile().swing_jitter));
+    if time <= swing_lead + jitter_lead {
+        actions.push(player, Action::Batter(BatterAction::SwingCCW));
+        *jitter = None;
+        return;
+    }
+    if time <= Batter::FULL_CHARGE_TIME + swing_lead {
+        actions.push(player, Action::Batter(BatterAction::ChargeSwing));
+    }
+    // the swing sweeps counter-clockwise, so wait half a sweep short of the ball
+    let half_sweep = bat.swing_velocity * batter.stamina_power() * swing_lead;
+    turn_towards(
+        &mut actions,
+        player,
+        bat_angle,
+        angle_of(meeting_point) - half_sweep,
+    );
+}
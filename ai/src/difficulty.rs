@@ -0,0 +1,90 @@
+use bevy_ecs::prelude::Resource;
+
+// How well a bot plays.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BotProfile {
+    // how long it takes to notice where the balls have gone, in seconds
+    pub reaction_delay: f32,
+    // how far off, in radians, the bot can misjudge the direction of a ball
+    pub prediction_error: f32,
+    // how early or late, in seconds, the bot can let go of a swing
+    pub swing_jitter: f32,
+    // the most balls the bot can keep track of at once, closest to the wicket first
+    pub attention: usize,
+}
+
+impl BotProfile {
+    pub const fn easy() -> Self {
+        BotProfile {
+            reaction_delay: 0.4,
+            prediction_error: 0.35,
+            swing_jitter: 0.12,
+            attention: 1,
+        }
+    }
+
+    pub const fn medium() -> Self {
+        BotProfile {
+            reaction_delay: 0.2,
+            prediction_error: 0.15,
+            swing_jitter: 0.06,
+            attention: 2,
+        }
+    }
+
+    pub const fn hard() -> Self {
+        BotProfile {
+            reaction_delay: 0.05,
+            prediction_error: 0.03,
+            swing_jitter: 0.015,
+            attention: 4,
+        }
+    }
+}
+
+impl Default for BotProfile {
+    fn default() -> Self {
+        BotProfile::medium()
+    }
+}
+
+// The difficulty every bot in the match plays at.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub enum BotDifficulty {
+    Easy,
+    #[default]
+    Medium,
+    Hard,
+    Custom(BotProfile),
+}
+
+impl BotDifficulty {
+    pub const ALL: [BotDifficulty; 4] = [
+        BotDifficulty::Easy,
+        BotDifficulty::Medium,
+        BotDifficulty::Hard,
+        BotDifficulty::Custom(BotProfile::medium()),
+    ];
+
+    pub const fn profile(&self) -> BotProfile {
+        match self {
+            BotDifficulty::Easy => BotProfile::easy(),
+            BotDifficulty::Medium => BotProfile::medium(),
+            BotDifficulty::Hard => BotProfile::hard(),
+            BotDifficulty::Custom(profile) => *profile,
+        }
+    }
+
+    pub const fn is_custom(&self) -> bool {
+        matches!(self, BotDifficulty::Custom(_))
+    }
+}
+
+impl std::fmt::Display for BotDifficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BotDifficulty::Custom(_) => write!(f, "Custom"),
+            _ => write!(f, "{:?}", self),
+        }
+    }
+}
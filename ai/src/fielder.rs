@@ -0,0 +1,116 @@
+use bevy_ecs::{
+    prelude::{Local, Query, Res, ResMut, State, With},
+    query::Has,
+};
+use bevy_time::prelude::Time;
+use bevy_transform::prelude::{GlobalTransform, Transform};
+
+use cricket_pong_game::{
+    actions::{Action, Actions, FielderAction},
+    base::{
+        batter::Batter,
+        fielder::{Fielder, FielderRing},
+        Bot, Identity, PlayerOne, Position,
+    },
+    BallCountdown, GamePhase,
+};
+
+use crate::{
+    predict::{angle_difference, angle_of, circle_exit, circle_intercept},
+    vision::BotVision,
+};
+
+// how close a fielder has to be to where it wants to be before the ring stops turning
+const AIM_TOLERANCE: f32 = 0.05;
+// bowl as soon as the bat is turned at least this far away from the bowler
+const BOWL_OPENING: f32 = std::f32::consts::FRAC_PI_2;
+// otherwise, bowl after waiting this long anyway
+const MAX_BOWL_WAIT: f32 = 2.;
+
+fn move_action(ring: FielderRing, turn: f32) -> Option<FielderAction> {
+    if turn.abs() <= AIM_TOLERANCE {
+        return None;
+    }
+    Some(match (ring, turn > 0.) {
+        (FielderRing::Infield, true) => FielderAction::MoveInfieldCCW,
+        (FielderRing::Infield, false) => FielderAction::MoveInfieldCW,
+        (FielderRing::Outfield, true) => FielderAction::MoveOutfieldCCW,
+        (FielderRing::Outfield, false) => FielderAction::MoveOutfieldCW,
+    })
+}
+
+// where the soonest ball will next cross the ring of the given radius, in either direction
+fn next_crossing(vision: &BotVision, radius: f32) -> Option<f32> {
+    vision
+        .seen()
+        .iter()
+        .filter_map(|ball| {
+            circle_intercept(ball.position, ball.velocity, radius)
+                .or_else(|| circle_exit(ball.position, ball.velocity, radius))
+        })
+        .min_by(|(time, _), (other_time, _)| time.total_cmp(other_time))
+        .map(|(_, point)| angle_of(point))
+}
+
+// bowls when the batter is caught facing the wrong way, then turns each ring so that
+// its closest fielder meets the ball where it will cross, to keep the passes going
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn play_fielder(
+    bot_query: Query<(&Position, Has<PlayerOne>), With<Bot>>,
+    fielder_query: Query<(&Fielder, &GlobalTransform)>,
+    batter_query: Query<&Transform, With<Batter>>,
+    vision: Res<BotVision>,
+    phase: Res<State<GamePhase>>,
+    countdown: Res<BallCountdown>,
+    time: Res<Time>,
+    mut actions: ResMut<Actions>,
+    mut waited: Local<f32>,
+) {
+    let Some(player) = bot_query.iter().find_map(|(position, is_player_one)| {
+        (*position == Position::Fielder).then_some(Identity::of(is_player_one))
+    }) else {
+        return;
+    };
+    if *phase == GamePhase::Preparing {
+        *waited = 0.;
+        if countdown.remaining.is_some() {
+            actions.push(player, Action::Fielder(FielderAction::Ready));
+        }
+        return;
+    }
+
+    if *phase == GamePhase::Bowling {
+        *waited += time.delta_seconds();
+        let bat_angle = batter_query
+            .get_single()
+            .map(|transform| angle_of(transform.translation.truncate()));
+        let bowler_angle = vision.seen().first().map(|ball| angle_of(ball.position));
+        let opening = match (bat_angle, bowler_angle) {
+            (Ok(bat_angle), Some(bowler_angle)) => {
+                angle_difference(bat_angle, bowler_angle).abs() >= BOWL_OPENING
+            }
+            _ => false,
+        };
+        if opening || *waited >= MAX_BOWL_WAIT {
+            actions.push(player, Action::Fielder(FielderAction::Bowl));
+        }
+        return;
+    }
+    if *phase != GamePhase::Active {
+        return;
+    }
+
+    for ring in [FielderRing::Infield, FielderRing::Outfield] {
+        let Some(target) = next_crossing(&vision, ring.radius()) else { continue };
+        let turn = fielder_query
+            .iter()
+            .filter(|(fielder, _)| fielder.ring == ring)
+            .map(|(_, transform)| {
+                angle_difference(angle_of(transform.translation().truncate()), target)
+            })
+            .min_by(|a, b| a.abs().total_cmp(&b.abs()));
+        if let Some(action) = turn.and_then(|turn| move_action(ring, turn)) {
+            actions.push(player, Action::Fielder(action));
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use bevy_app::prelude::{App, Plugin, PreUpdate};
+use bevy_ecs::prelude::{IntoSystemConfigs, SystemSet};
+
+use cricket_pong_game::{actions::Actions, GameplayMarkerPlugin};
+
+mod batter;
+mod difficulty;
+mod fielder;
+mod predict;
+mod vision;
+
+pub use difficulty::{BotDifficulty, BotProfile};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, SystemSet)]
+pub struct BotSet;
+
+// Plays for every player marked as a `Bot`, pushing actions into `Actions`
+// in the same way the player controllers do for humans.
+pub struct BotPlugin;
+
+impl Plugin for BotPlugin {
+    fn build(&self, app: &mut App) {
+        assert!(GameplayMarkerPlugin::is_added(app));
+        app.init_resource::<Actions>()
+            .init_resource::<BotDifficulty>()
+            .init_resource::<vision::BotRng>()
+            .init_resource::<vision::BotVision>()
+            .add_systems(
+                PreUpdate,
+                (
+                    vision::reseed_bots,
+                    vision::watch_balls,
+                    (batter::play_batter, fielder::play_fielder),
+                )
+                    .chain()
+                    .in_set(BotSet),
+            );
+    }
+}
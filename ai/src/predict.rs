@@ -0,0 +1,45 @@
+use bevy_math::Vec2;
+
+// when a ball at `position` moving with `velocity` will reach the circle of `radius`
+// around the wicket from outside it, and where it will cross it
+pub(crate) fn circle_intercept(position: Vec2, velocity: Vec2, radius: f32) -> Option<(f32, Vec2)> {
+    // solve |position + velocity * time| = radius
+    let a = velocity.length_squared();
+    if a <= f32::EPSILON {
+        return None;
+    }
+    let b = 2. * position.dot(velocity);
+    let c = position.length_squared() - radius * radius;
+    let discriminant = b * b - 4. * a * c;
+    if discriminant < 0. {
+        return None;
+    }
+    let time = (-b - discriminant.sqrt()) / (2. * a);
+    if time < 0. {
+        return None;
+    }
+    Some((time, position + velocity * time))
+}
+
+// the angle of a point around the wicket, counter-clockwise from the top
+pub(crate) fn angle_of(point: Vec2) -> f32 {
+    Vec2::Y.angle_between(point)
+}
+
+// the shortest signed turn from one angle to another
+pub(crate) fn angle_difference(from: f32, to: f32) -> f32 {
+    (to - from + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI
+}
+
+// when a ball at `position` inside the circle of `radius` around the wicket will leave it,
+// and where it will cross it
+pub(crate) fn circle_exit(position: Vec2, velocity: Vec2, radius: f32) -> Option<(f32, Vec2)> {
+    let a = velocity.length_squared();
+    let c = position.length_squared() - radius * radius;
+    if a <= f32::EPSILON || c > 0. {
+        return None;
+    }
+    let b = 2. * position.dot(velocity);
+    let time = (-b + (b * b - 4. * a * c).sqrt()) / (2. * a);
+    Some((time, position + velocity * time))
+}
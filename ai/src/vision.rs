@@ -0,0 +1,135 @@
+use std::collections::{HashMap, VecDeque};
+
+use bevy_ecs::prelude::{Added, Entity, Query, Res, ResMut, Resource, With};
+use bevy_math::Vec2;
+use bevy_time::prelude::Time;
+use bevy_transform::prelude::GlobalTransform;
+
+use bevy_rapier2d::prelude::Velocity;
+
+use cricket_pong_game::{
+    base::{ball::Ball, Bot},
+    random::MatchRng,
+    MatchRules,
+};
+
+use crate::difficulty::BotDifficulty;
+
+// a ball whose speed changes by more than this has been hit, and is judged afresh
+const REJUDGE_SPEED: f32 = 5.;
+
+// Where a bot believes a ball to be.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Sighting {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub radius: f32,
+}
+
+// Randomness for the bots' mistakes, kept apart from the MatchRng so that
+// bots don't change how the rest of the match plays out.
+#[derive(Resource, Default)]
+pub(crate) struct BotRng(pub MatchRng);
+
+// mixed into the match seed, so that the bots' mistakes don't follow the match's own rolls
+const BOT_SEED_SALT: u64 = 0x9e37_79b9_7f4a_7c15;
+
+// should be run when a bot joins the match
+pub(crate) fn reseed_bots(
+    bot_query: Query<(), Added<Bot>>,
+    rules: Res<MatchRules>,
+    mut rng: ResMut<BotRng>,
+) {
+    if !bot_query.is_empty() {
+        rng.0.reseed(rules.seed ^ BOT_SEED_SALT);
+    }
+}
+
+// What the bots have seen of the balls.
+#[derive(Resource, Default)]
+pub(crate) struct BotVision {
+    // recent snapshots of every ball, oldest first
+    history: VecDeque<(f32, Vec<(Entity, Sighting)>)>,
+    // the velocity each ball was last judged at, and how far off that judgement was
+    judgements: HashMap<Entity, (Vec2, f32)>,
+    seen: Vec<Sighting>,
+}
+
+impl BotVision {
+    // the balls the bots are paying attention to, as they believe them to be now
+    pub(crate) fn seen(&self) -> &[Sighting] {
+        &self.seen
+    }
+}
+
+// bots see the balls as they were a reaction time ago, misjudge their paths a little,
+// and lose track of any beyond what they can pay attention to
+pub(crate) fn watch_balls(
+    ball_query: Query<(Entity, &Ball, &GlobalTransform, &Velocity)>,
+    bot_query: Query<(), With<Bot>>,
+    difficulty: Res<BotDifficulty>,
+    time: Res<Time>,
+    mut rng: ResMut<BotRng>,
+    mut vision: ResMut<BotVision>,
+) {
+    if bot_query.is_empty() {
+        return;
+    }
+    let profile = difficulty.profile();
+    let now = time.elapsed_seconds();
+    let snapshot = ball_query
+        .iter()
+        .map(|(entity, ball, transform, velocity)| {
+            let sighting = Sighting {
+                position: transform.translation().truncate(),
+                velocity: velocity.linvel,
+                radius: ball.kind.radius(),
+            };
+            (entity, sighting)
+        })
+        .collect();
+    let BotVision {
+        history,
+        judgements,
+        seen,
+    } = &mut *vision;
+    history.push_back((now, snapshot));
+    // keep the newest snapshot that is at least a reaction time old
+    while history
+        .get(1)
+        .is_some_and(|(taken, _)| *taken <= now - profile.reaction_delay)
+    {
+        history.pop_front();
+    }
+    let Some((taken, remembered)) = history.front() else { return };
+    let elapsed = now - taken;
+
+    judgements.retain(|entity, _| remembered.iter().any(|(other, _)| other == entity));
+    seen.clear();
+    for (entity, sighting) in remembered {
+        let judgement = judgements
+            .get(entity)
+            .copied()
+            .filter(|(judged, _)| judged.distance(sighting.velocity) <= REJUDGE_SPEED);
+        let error = match judgement {
+            Some((_, error)) => error,
+            None => {
+                let error = rng.0.spread(profile.prediction_error);
+                judgements.insert(*entity, (sighting.velocity, error));
+                error
+            }
+        };
+        let velocity = Vec2::from_angle(error).rotate(sighting.velocity);
+        seen.push(Sighting {
+            position: sighting.position + velocity * elapsed,
+            velocity,
+            radius: sighting.radius,
+        });
+    }
+    seen.sort_by(|a, b| {
+        a.position
+            .length_squared()
+            .total_cmp(&b.position.length_squared())
+    });
+    seen.truncate(profile.attention);
+}
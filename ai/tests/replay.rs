@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use bevy_ecs::prelude::{Commands, OnEnter, States, SystemSet};
+use bevy_time::TimeUpdateStrategy;
+
+use cricket_pong_ai::BotPlugin;
+use cricket_pong_game::{
+    actions::Actions,
+    base::{Bot, PlayerOne, PlayerTwo, Position, Score},
+    GameplayPlugin, MatchRules, Over,
+};
+use cricket_pong_support::headless_app;
+
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, States)]
+enum TestScreen {
+    #[default]
+    Match,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, SystemSet)]
+struct TestSet;
+
+// every frame is the same length, so that only the seed decides how the match plays out
+const FRAME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+const FRAMES: usize = 60 * 30;
+
+fn spawn_bots(mut commands: Commands) {
+    commands.spawn((Position::Batter, PlayerOne, Score(0), Bot));
+    commands.spawn((Position::Fielder, PlayerTwo, Score(0), Bot));
+}
+
+// plays a match between two bots, returning the balls bowled in it
+fn play_bot_match(seed: u64) -> Over {
+    let mut app = headless_app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(FRAME))
+        .insert_resource(MatchRules {
+            seed,
+            ..Default::default()
+        })
+        .init_resource::<Actions>()
+        .add_state::<TestScreen>()
+        .add_plugins(GameplayPlugin::new(TestSet, TestScreen::Match))
+        .add_plugins(BotPlugin)
+        .add_systems(OnEnter(TestScreen::Match), spawn_bots);
+    for _ in 0..FRAMES {
+        app.update();
+    }
+    app.world.resource::<Over>().clone()
+}
+
+#[test]
+fn seeded_bot_matches_play_out_the_same() {
+    let first = play_bot_match(7);
+    assert!(!first.is_empty());
+    assert_eq!(first, play_bot_match(7));
+}
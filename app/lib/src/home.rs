@@ -1,23 +1,186 @@
 use bevy::prelude::*;
 
 use bevy_egui::{
-    egui::{CentralPanel, Vec2},
+    egui::{Button, CentralPanel, ComboBox, Slider, TextEdit, Ui, Vec2},
     EguiContexts, EguiPlugin,
 };
 
-use crate::AppScreen;
+use cricket_pong_ai::BotDifficulty;
+use cricket_pong_game::base::{batter::BatKind, Identity};
+use cricket_pong_lobby::{LobbyAddress, LobbyClient, LobbyError, RoomChoice, RoomCode};
+use cricket_pong_net::{ClientRole, ServerAddress, SessionToken};
 
-fn home_menu(mut egui_ctx: EguiContexts, mut screen_state: ResMut<NextState<AppScreen>>) {
+use crate::{AppScreen, MatchSetup};
+
+fn bat_picker(ui: &mut Ui, label: &str, bat: &mut BatKind) {
+    ComboBox::from_label(label)
+        .selected_text(bat.to_string())
+        .show_ui(ui, |ui| {
+            for kind in BatKind::ALL {
+                ui.selectable_value(bat, kind, kind.to_string());
+            }
+        });
+}
+
+fn difficulty_picker(ui: &mut Ui, difficulty: &mut BotDifficulty) {
+    ComboBox::from_label("CPU difficulty")
+        .selected_text(difficulty.to_string())
+        .show_ui(ui, |ui| {
+            for level in BotDifficulty::ALL {
+                // keep any tuning already done when picking Custom again
+                let selected = std::mem::discriminant(difficulty) == std::mem::discriminant(&level);
+                if ui.selectable_label(selected, level.to_string()).clicked() && !selected {
+                    *difficulty = level;
+                }
+            }
+        });
+    if let BotDifficulty::Custom(profile) = difficulty {
+        ui.add(Slider::new(&mut profile.reaction_delay, 0.0..=1.0).text("Reaction delay"));
+        ui.add(Slider::new(&mut profile.prediction_error, 0.0..=1.0).text("Prediction error"));
+        ui.add(Slider::new(&mut profile.swing_jitter, 0.0..=0.3).text("Swing jitter"));
+        ui.add(Slider::new(&mut profile.attention, 1..=4).text("Attention"));
+    }
+}
+
+fn home_menu(
+    mut commands: Commands,
+    mut egui_ctx: EguiContexts,
+    mut screen_state: ResMut<NextState<AppScreen>>,
+    mut setup: ResMut<MatchSetup>,
+) {
     CentralPanel::default().show(egui_ctx.ctx_mut(), |ui| {
         ui.centered_and_justified(|ui| {
-            ui.set_height(300.);
+            ui.set_height(600.);
             ui.set_width(200.);
             ui.vertical_centered(|ui| {
+                bat_picker(ui, "Player One bat", &mut setup.player_one_bat);
+                bat_picker(ui, "Player Two bat", &mut setup.player_two_bat);
+                difficulty_picker(ui, &mut setup.difficulty);
                 ui.allocate_ui(Vec2::new(200., 80.), |ui| {
                     if ui.button("Play Locally").clicked() {
                         screen_state.set(AppScreen::LocalGame);
                     }
+                    if ui.button("Play vs CPU").clicked() {
+                        screen_state.set(AppScreen::AIGame);
+                    }
                 });
+                ui.add(TextEdit::singleline(&mut setup.server_address).hint_text("Server address"));
+                // the button stays disabled until the address can be used
+                let address = setup.server_address.trim().parse::<ServerAddress>().ok();
+                if ui
+                    .add_enabled(address.is_some(), Button::new("Play Online"))
+                    .clicked()
+                {
+                    if let Some(address) = address.clone() {
+                        commands.insert_resource(address);
+                        commands.insert_resource(ClientRole::Player);
+                        // a token from an earlier match means nothing to this server
+                        commands.remove_resource::<SessionToken>();
+                        screen_state.set(AppScreen::OnlineGame);
+                    }
+                }
+                if ui
+                    .add_enabled(address.is_some(), Button::new("Watch Online"))
+                    .clicked()
+                {
+                    if let Some(address) = address {
+                        commands.insert_resource(address);
+                        commands.insert_resource(ClientRole::Spectator);
+                        commands.remove_resource::<SessionToken>();
+                        screen_state.set(AppScreen::OnlineGame);
+                    }
+                }
+                ui.add(TextEdit::singleline(&mut setup.lobby_address).hint_text("Lobby address"));
+                let lobby = setup.lobby_address.trim().parse::<ServerAddress>().ok();
+                if ui
+                    .add_enabled(lobby.is_some(), Button::new("Host"))
+                    .clicked()
+                {
+                    if let Some(lobby) = lobby.clone() {
+                        commands.insert_resource(LobbyAddress(lobby));
+                        commands.insert_resource(RoomChoice::Host);
+                        screen_state.set(AppScreen::Lobby);
+                    }
+                }
+                ui.add(TextEdit::singleline(&mut setup.room_code).hint_text("Room code"));
+                let code = RoomCode::parse(&setup.room_code);
+                if ui
+                    .add_enabled(
+                        lobby.is_some() && code.is_some(),
+                        Button::new("Join by code"),
+                    )
+                    .clicked()
+                {
+                    if let (Some(lobby), Some(code)) = (lobby, code) {
+                        commands.insert_resource(LobbyAddress(lobby));
+                        commands.insert_resource(RoomChoice::Join(code));
+                        screen_state.set(AppScreen::Lobby);
+                    }
+                }
+            });
+        });
+    });
+}
+
+// shows the room while the players get ready, and moves on to the match once it has a server
+fn lobby_room(
+    mut commands: Commands,
+    mut egui_ctx: EguiContexts,
+    mut screen_state: ResMut<NextState<AppScreen>>,
+    mut client: ResMut<LobbyClient>,
+) {
+    if let (Some(server), Some(token)) = (client.match_server(), client.match_token()) {
+        commands.insert_resource(server);
+        commands.insert_resource(token);
+        commands.insert_resource(ClientRole::Player);
+        screen_state.set(AppScreen::OnlineGame);
+        return;
+    }
+    CentralPanel::default().show(egui_ctx.ctx_mut(), |ui| {
+        ui.centered_and_justified(|ui| {
+            ui.set_height(240.);
+            ui.set_width(200.);
+            ui.vertical_centered(|ui| {
+                match (client.room(), client.error()) {
+                    (_, Some(LobbyError::Unreachable)) => {
+                        ui.label("The lobby couldn't be reached");
+                    }
+                    (_, Some(LobbyError::NotFound)) => {
+                        ui.label("There is no room with that code");
+                    }
+                    (_, Some(LobbyError::Full)) => {
+                        ui.label("That room is full");
+                    }
+                    (None, None) => {
+                        ui.label("Finding a room...");
+                    }
+                    (Some(room), None) => {
+                        ui.heading(format!("Room {}", room.code));
+                        for seat in room.seats.iter() {
+                            let name = match seat.player {
+                                Identity::One => "Player One",
+                                Identity::Two => "Player Two",
+                            };
+                            let you = if seat.player == room.you {
+                                " (you)"
+                            } else {
+                                ""
+                            };
+                            let status = if seat.ready { "ready" } else { "not ready" };
+                            ui.label(format!("{name}{you}: {status}"));
+                        }
+                        if room.seats.len() < 2 {
+                            ui.label("Waiting for someone to join...");
+                        }
+                        let mut ready = client.is_ready();
+                        if ui.checkbox(&mut ready, "Ready").changed() {
+                            client.set_ready(ready);
+                        }
+                    }
+                }
+                if ui.button("Leave").clicked() {
+                    screen_state.set(AppScreen::MainMenu);
+                }
             });
         });
     });
@@ -30,6 +193,7 @@ impl Plugin for HomeScreenPlugin {
         if !app.is_plugin_added::<EguiPlugin>() {
             app.add_plugins(EguiPlugin);
         }
-        app.add_systems(Update, home_menu.run_if(in_state(AppScreen::MainMenu)));
+        app.add_systems(Update, home_menu.run_if(in_state(AppScreen::MainMenu)))
+            .add_systems(Update, lobby_room.run_if(in_state(AppScreen::Lobby)));
     }
 }
@@ -1,14 +1,17 @@
 use bevy::prelude::{
-    in_state, App, Commands, DefaultPlugins, IntoSystemSetConfig, OnEnter, PluginGroup, States,
-    SystemSet, Update, Window, WindowPlugin,
+    in_state, App, Commands, DefaultPlugins, IntoSystemSetConfig, OnEnter, PluginGroup, Res,
+    Resource, States, SystemSet, Update, Window, WindowPlugin,
 };
 
+use cricket_pong_ai::{BotDifficulty, BotPlugin};
 use cricket_pong_controls::PlayerControllerPlugin;
 use cricket_pong_game::{
-    base::{PlayerOne, PlayerTwo, Position, Score},
+    base::{batter::BatKind, Bot, PlayerOne, PlayerTwo, Position, Score},
     GamePhase, GameplayPlugin,
 };
 use cricket_pong_graphics::GraphicsPlugin;
+use cricket_pong_lobby::LobbyClientPlugin;
+use cricket_pong_net::ClientPlugin;
 use home::HomeScreenPlugin;
 
 mod home;
@@ -16,25 +19,79 @@ mod home;
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, SystemSet)]
 pub struct LocalGameplaySet;
 
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, SystemSet)]
+pub struct AIGameplaySet;
+
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, States)]
 enum AppScreen {
     Splash,
     #[default]
     MainMenu,
     LocalGame,
-    // ** TODO:
-    // AIGame,
-    // OnlineGame,
+    AIGame,
+    // in a room on the lobby, waiting for the match to start
+    Lobby,
+    OnlineGame,
+}
+
+// browsers can only reach the server over websockets
+#[cfg(target_arch = "wasm32")]
+const DEFAULT_SERVER_ADDRESS: &str = "ws://127.0.0.1:7778";
+#[cfg(not(target_arch = "wasm32"))]
+const DEFAULT_SERVER_ADDRESS: &str = "127.0.0.1:7777";
+#[cfg(target_arch = "wasm32")]
+const DEFAULT_LOBBY_ADDRESS: &str = "ws://127.0.0.1:7781";
+#[cfg(not(target_arch = "wasm32"))]
+const DEFAULT_LOBBY_ADDRESS: &str = "127.0.0.1:7780";
+
+// choices the players make in the menu before starting a match
+#[derive(Resource)]
+struct MatchSetup {
+    player_one_bat: BatKind,
+    player_two_bat: BatKind,
+    difficulty: BotDifficulty,
+    server_address: String,
+    lobby_address: String,
+    // the code of someone else's room to join
+    room_code: String,
+}
+
+impl Default for MatchSetup {
+    fn default() -> Self {
+        MatchSetup {
+            player_one_bat: BatKind::default(),
+            player_two_bat: BatKind::default(),
+            difficulty: BotDifficulty::default(),
+            server_address: DEFAULT_SERVER_ADDRESS.to_string(),
+            lobby_address: DEFAULT_LOBBY_ADDRESS.to_string(),
+            room_code: String::new(),
+        }
+    }
 }
 
-fn spawn_local_players(mut commands: Commands) {
-    commands.spawn((Position::Batter, PlayerOne, Score(0)));
-    commands.spawn((Position::Fielder, PlayerTwo, Score(0)));
+fn spawn_local_players(mut commands: Commands, setup: Res<MatchSetup>) {
+    commands.spawn((Position::Batter, PlayerOne, Score(0), setup.player_one_bat));
+    commands.spawn((Position::Fielder, PlayerTwo, Score(0), setup.player_two_bat));
+}
+
+// the computer bats first while the human player takes the field,
+// and they swap over after the first over as usual
+fn spawn_ai_players(mut commands: Commands, setup: Res<MatchSetup>) {
+    commands.insert_resource(setup.difficulty);
+    commands.spawn((Position::Fielder, PlayerOne, Score(0), setup.player_one_bat));
+    commands.spawn((
+        Position::Batter,
+        PlayerTwo,
+        Score(0),
+        setup.player_two_bat,
+        Bot,
+    ));
 }
 
 pub fn run_app(canvas: Option<String>) {
     App::default()
         .add_state::<AppScreen>()
+        .init_resource::<MatchSetup>()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 canvas,
@@ -47,15 +104,28 @@ pub fn run_app(canvas: Option<String>) {
             Update,
             LocalGameplaySet.run_if(in_state(AppScreen::LocalGame)),
         )
+        .configure_set(Update, AIGameplaySet.run_if(in_state(AppScreen::AIGame)))
         .add_plugins(GameplayPlugin::new(LocalGameplaySet, AppScreen::LocalGame))
+        .add_plugins(GameplayPlugin::new(AIGameplaySet, AppScreen::AIGame))
         .add_plugins((
             PlayerControllerPlugin,
+            BotPlugin,
+            // online matches are played on the server, so only the client is added here
+            ClientPlugin::new(AppScreen::OnlineGame),
+            LobbyClientPlugin::new(AppScreen::Lobby),
             GraphicsPlugin::new(
                 AppScreen::LocalGame,
                 AppScreen::MainMenu,
                 GamePhase::GameOver,
             ),
+            GraphicsPlugin::new(AppScreen::AIGame, AppScreen::MainMenu, GamePhase::GameOver),
+            GraphicsPlugin::new(
+                AppScreen::OnlineGame,
+                AppScreen::MainMenu,
+                GamePhase::GameOver,
+            ),
         ))
         .add_systems(OnEnter(AppScreen::LocalGame), spawn_local_players)
+        .add_systems(OnEnter(AppScreen::AIGame), spawn_ai_players)
         .run();
 }
@@ -0,0 +1,43 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use bevy::app::ScheduleRunnerPlugin;
+
+use cricket_pong_lobby::LobbyServerPlugin;
+use cricket_pong_net::headless_app;
+
+const DEFAULT_ADDRESS: &str = "0.0.0.0:7780";
+const DEFAULT_WEBSOCKET_ADDRESS: &str = "0.0.0.0:7781";
+const DEFAULT_PUBLIC_HOST: &str = "127.0.0.1";
+const TICK_RATE: f64 = 30.;
+
+// Runs the lobby at the address given as the first argument, and for browsers, over
+// websockets at the address given as the second. Each match is hosted on a game server
+// of its own, which players are told to reach at the host given as the third argument.
+fn main() {
+    let address: SocketAddr = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDRESS.to_string())
+        .parse()
+        .expect("the lobby address should look like 0.0.0.0:7780");
+    let websocket_address: SocketAddr = std::env::args()
+        .nth(2)
+        .unwrap_or_else(|| DEFAULT_WEBSOCKET_ADDRESS.to_string())
+        .parse()
+        .expect("the websocket address should look like 0.0.0.0:7781");
+    let public_host: IpAddr = std::env::args()
+        .nth(3)
+        .unwrap_or_else(|| DEFAULT_PUBLIC_HOST.to_string())
+        .parse()
+        .expect("the public host should look like 127.0.0.1");
+    headless_app()
+        .add_plugins((
+            ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(1. / TICK_RATE)),
+            LobbyServerPlugin::new(address)
+                .with_websocket(websocket_address)
+                .with_public_host(public_host),
+        ))
+        .run();
+}
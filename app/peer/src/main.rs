@@ -0,0 +1,78 @@
+use std::net::SocketAddr;
+
+use bevy::prelude::{App, Commands, DefaultPlugins, OnEnter, Res, Resource, States, SystemSet};
+
+use cricket_pong_controls::PlayerControllerPlugin;
+use cricket_pong_game::{
+    base::{Identity, PlayerOne, PlayerTwo, Position, Remote, Score},
+    GamePhase, GameplayPlugin,
+};
+use cricket_pong_graphics::GraphicsPlugin;
+use cricket_pong_rollback::{GameplayStep, RollbackMode, RollbackPlugin};
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, SystemSet)]
+struct PeerGameplaySet;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, States)]
+enum PeerScreen {
+    #[default]
+    Match,
+    Finished,
+}
+
+// which of the two players is sitting at this machine
+#[derive(Resource)]
+struct LocalPlayer(Identity);
+
+fn spawn_players(mut commands: Commands, local_player: Res<LocalPlayer>) {
+    let mut player_one = commands.spawn((Position::Batter, PlayerOne, Score(0)));
+    if local_player.0 != Identity::One {
+        player_one.insert(Remote);
+    }
+    let mut player_two = commands.spawn((Position::Fielder, PlayerTwo, Score(0)));
+    if local_player.0 != Identity::Two {
+        player_two.insert(Remote);
+    }
+}
+
+// Plays a rollback match against another copy of this program, run by the other player.
+// Usage: cricket_pong_app_peer <1 or 2> <local address> <peer address>
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let local_player = args
+        .next()
+        .and_then(|player| player.parse::<u8>().ok())
+        .and_then(|player| Identity::try_from(player).ok())
+        .expect("the first argument should be which player this is, 1 or 2");
+    let local_address: SocketAddr = args
+        .next()
+        .and_then(|address| address.parse().ok())
+        .expect("the second argument should be the address to play from, like 0.0.0.0:7000");
+    let peer_address: SocketAddr = args
+        .next()
+        .and_then(|address| address.parse().ok())
+        .expect("the third argument should be the other player's address, like 10.0.0.2:7000");
+
+    App::new()
+        .add_state::<PeerScreen>()
+        .insert_resource(LocalPlayer(local_player))
+        .add_plugins(DefaultPlugins)
+        .add_plugins(
+            GameplayPlugin::new(PeerGameplaySet, PeerScreen::Match).in_schedule(GameplayStep),
+        )
+        .add_plugins((
+            RollbackPlugin::new(
+                PeerGameplaySet,
+                PeerScreen::Match,
+                RollbackMode::PeerToPeer {
+                    local_player,
+                    local_address,
+                    peer_address,
+                },
+            ),
+            PlayerControllerPlugin,
+            GraphicsPlugin::new(PeerScreen::Match, PeerScreen::Finished, GamePhase::GameOver),
+        ))
+        .add_systems(OnEnter(PeerScreen::Match), spawn_players)
+        .run();
+}
@@ -0,0 +1,30 @@
+use std::{net::SocketAddr, time::Duration};
+
+use bevy::app::ScheduleRunnerPlugin;
+
+use cricket_pong_net::{headless_app, ServerPlugin};
+
+const DEFAULT_ADDRESS: &str = "0.0.0.0:7777";
+const DEFAULT_WEBSOCKET_ADDRESS: &str = "0.0.0.0:7778";
+const TICK_RATE: f64 = 60.;
+
+// Hosts a single online match at the address given as the first argument,
+// and for browsers, over websockets at the address given as the second.
+fn main() {
+    let address: SocketAddr = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDRESS.to_string())
+        .parse()
+        .expect("the server address should look like 0.0.0.0:7777");
+    let websocket_address: SocketAddr = std::env::args()
+        .nth(2)
+        .unwrap_or_else(|| DEFAULT_WEBSOCKET_ADDRESS.to_string())
+        .parse()
+        .expect("the websocket address should look like 0.0.0.0:7778");
+    headless_app()
+        .add_plugins((
+            ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(1. / TICK_RATE)),
+            ServerPlugin::new(address).with_websocket(websocket_address),
+        ))
+        .run();
+}
@@ -0,0 +1,35 @@
+use bevy_ecs::prelude::Resource;
+
+// The wait before the next ball can be bowled, and which sides have said they are ready.
+// `remaining` is only set while the game is waiting between balls.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct BallCountdown {
+    pub remaining: Option<f32>,
+    pub batter_ready: bool,
+    pub fielder_ready: bool,
+}
+
+// Time the fielding side has left to bowl, while a shot clock is running.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct ShotClock {
+    pub remaining: Option<f32>,
+}
+
+// Time the match has spent with the ball in play, which the wind gusts follow.
+// It carries on from one delivery to the next, and only starts again with the match.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct GustClock {
+    pub elapsed: f32,
+}
+
+// Time since the last extra ball was released, in multi-ball matches.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct ExtraBallTimer {
+    pub elapsed: f32,
+}
+
+// Time since the last power-up was placed, in matches with power-ups.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct PowerUpTimer {
+    pub elapsed: f32,
+}
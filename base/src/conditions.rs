@@ -0,0 +1,102 @@
+use bevy_ecs::prelude::{Component, Resource};
+use bevy_math::Vec2;
+
+// The weather for the current match, pushing the ball around while it is in play.
+#[derive(Resource, Clone, Debug)]
+pub struct Conditions {
+    // steady force applied to the ball
+    pub wind: Vec2,
+    // how much stronger than the steady wind a gust can get, as a fraction of it
+    pub gust_strength: f32,
+    // seconds between the peaks of two gusts
+    pub gust_period: f32,
+    // from 0 (dry) to 1 (humid); humid air makes a spinning ball curve more
+    pub humidity: f32,
+}
+
+impl Conditions {
+    pub const MAX_WIND: f32 = 80.;
+    pub const SPIN_EFFECT: f32 = 0.05;
+
+    pub fn calm() -> Self {
+        Conditions {
+            wind: Vec2::ZERO,
+            gust_strength: 0.,
+            gust_period: 1.,
+            humidity: 0.3,
+        }
+    }
+
+    pub fn breezy() -> Self {
+        Conditions {
+            wind: Vec2::new(40., 0.),
+            gust_strength: 0.25,
+            gust_period: 4.,
+            humidity: 0.4,
+        }
+    }
+
+    pub fn gusty() -> Self {
+        Conditions {
+            wind: Vec2::new(0., -50.),
+            gust_strength: 0.8,
+            gust_period: 2.,
+            humidity: 0.3,
+        }
+    }
+
+    pub fn humid() -> Self {
+        Conditions {
+            wind: Vec2::new(10., 10.),
+            gust_strength: 0.,
+            gust_period: 1.,
+            humidity: 0.9,
+        }
+    }
+
+    // the wind force once balls have been in play for `elapsed` seconds of the match
+    pub fn wind_at(&self, elapsed: f32) -> Vec2 {
+        let gust_phase = std::f32::consts::TAU * elapsed / self.gust_period.max(f32::EPSILON);
+        self.wind * (1. + self.gust_strength * gust_phase.sin().max(0.))
+    }
+
+    // sideways force on a ball moving with `velocity` while spinning at `angvel`
+    pub fn spin_force(&self, velocity: Vec2, angvel: f32) -> Vec2 {
+        velocity.perp() * angvel * Self::SPIN_EFFECT * (0.5 + self.humidity)
+    }
+}
+
+impl Default for Conditions {
+    fn default() -> Self {
+        Conditions::calm()
+    }
+}
+
+// How the conditions for a match are chosen.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Weather {
+    #[default]
+    Calm,
+    Breezy,
+    Gusty,
+    Humid,
+    // rolled from the match seed
+    Random,
+}
+
+impl Weather {
+    // the preset conditions for this weather, if it is not random
+    pub fn preset(&self) -> Option<Conditions> {
+        match self {
+            Weather::Calm => Some(Conditions::calm()),
+            Weather::Breezy => Some(Conditions::breezy()),
+            Weather::Gusty => Some(Conditions::gusty()),
+            Weather::Humid => Some(Conditions::humid()),
+            Weather::Random => None,
+        }
+    }
+}
+
+// Marks the wind indicator drawn on the field.
+#[derive(Component, Clone)]
+pub struct WindVane;
@@ -0,0 +1,144 @@
+use bevy_math::{Quat, Vec2};
+
+// The outline of the ground. The boundary and both fielder rings are this same curve,
+// scaled to their own nominal radius.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GroundShape {
+    #[default]
+    Circle,
+    // stretched across and along the field by these fractions of the nominal radius
+    Ellipse { width: f32, height: f32 },
+    // a regular polygon with the middle of each edge on the nominal radius, one at the top
+    Polygon { sides: u8 },
+}
+
+impl GroundShape {
+    // how many points to use when a smooth curve is drawn or given a collider
+    pub const RESOLUTION: usize = 64;
+
+    // a traditional oval ground, wider than it is long
+    pub const fn oval() -> Self {
+        GroundShape::Ellipse {
+            width: 1.25,
+            height: 1.,
+        }
+    }
+
+    // the point on the curve of the given size at `angle` (counter-clockwise from the top)
+    pub fn point(&self, radius: f32, angle: f32) -> Vec2 {
+        let direction = Vec2::from_angle(angle).rotate(Vec2::Y);
+        match *self {
+            GroundShape::Circle => direction * radius,
+            GroundShape::Ellipse { width, height } => {
+                Vec2::new(direction.x * width, direction.y * height) * radius
+            }
+            GroundShape::Polygon { sides } => {
+                let half_sector = std::f32::consts::PI / sides.max(3) as f32;
+                // how far this direction is from the middle of the nearest edge
+                let offset = (angle + half_sector).rem_euclid(half_sector * 2.) - half_sector;
+                direction * radius / offset.cos()
+            }
+        }
+    }
+
+    // the direction out of the ground at `angle` on the curve of the given size
+    pub fn normal(&self, radius: f32, angle: f32) -> Vec2 {
+        const STEP: f32 = 0.001;
+        let tangent = self.point(radius, angle + STEP) - self.point(radius, angle - STEP);
+        -tangent.perp().normalize_or_zero()
+    }
+
+    // the rotation of something standing on the curve and facing the middle of the ground
+    pub fn facing(&self, radius: f32, angle: f32) -> Quat {
+        Quat::from_rotation_z(Vec2::Y.angle_between(self.normal(radius, angle)))
+    }
+
+    // the size of the curve that passes through `position`
+    pub fn nominal_radius(&self, position: Vec2) -> f32 {
+        match *self {
+            GroundShape::Circle => position.length(),
+            // the point's angle along an ellipse isn't its angle from the middle,
+            // so undo the stretch instead
+            GroundShape::Ellipse { width, height } => {
+                Vec2::new(position.x / width, position.y / height).length()
+            }
+            GroundShape::Polygon { .. } => {
+                if position == Vec2::ZERO {
+                    return 0.;
+                }
+                let angle = Vec2::Y.angle_between(position);
+                position.length() / self.point(1., angle).length()
+            }
+        }
+    }
+
+    // points around the whole curve of the given size, for colliders and drawing
+    pub fn outline(&self, radius: f32) -> Vec<Vec2> {
+        // polygons only need their corners, which sit between the middles of the edges
+        let (count, start) = match *self {
+            GroundShape::Polygon { sides } => {
+                let sides = sides.max(3) as usize;
+                (sides, std::f32::consts::PI / sides as f32)
+            }
+            _ => (Self::RESOLUTION, 0.),
+        };
+        (0..count)
+            .map(|index| {
+                let angle = start + std::f32::consts::TAU * index as f32 / count as f32;
+                self.point(radius, angle)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHAPES: [GroundShape; 3] = [
+        GroundShape::Circle,
+        GroundShape::oval(),
+        GroundShape::Polygon { sides: 6 },
+    ];
+
+    #[test]
+    fn points_lie_on_their_own_curve() {
+        for shape in SHAPES {
+            for index in 0..16 {
+                let angle = std::f32::consts::TAU * index as f32 / 16.;
+                let point = shape.point(300., angle);
+                let radius = shape.nominal_radius(point);
+                assert!(
+                    (radius - 300.).abs() < 0.01,
+                    "{shape:?} at {angle}: {radius}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ellipse_is_stretched_along_each_axis() {
+        let shape = GroundShape::oval();
+        assert_eq!(shape.nominal_radius(Vec2::new(125., 0.)), 100.);
+        assert_eq!(shape.nominal_radius(Vec2::new(0., 100.)), 100.);
+        // a point halfway along the diagonal of the stretched square is inside the curve
+        assert!(shape.nominal_radius(Vec2::new(62.5, 50.)) < 100.);
+    }
+
+    #[test]
+    fn polygon_edges_touch_the_nominal_radius() {
+        let shape = GroundShape::Polygon { sides: 4 };
+        assert!((shape.point(100., 0.) - Vec2::new(0., 100.)).length() < 0.001);
+        // the corners stick out beyond it
+        let corner = shape.point(100., std::f32::consts::FRAC_PI_4);
+        assert!((corner.length() - 100. * std::f32::consts::SQRT_2).abs() < 0.01);
+        assert_eq!(shape.outline(100.).len(), 4);
+    }
+
+    #[test]
+    fn middle_of_the_ground_has_no_size() {
+        for shape in SHAPES {
+            assert_eq!(shape.nominal_radius(Vec2::ZERO), 0.);
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{
+    fielder::FielderRing,
+    obstacle::{Obstacle, ObstacleKind},
+};
+
+// Anything placed on the field on top of the usual bat, fielders and boundary.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FieldLayout {
+    pub obstacles: Vec<Obstacle>,
+}
+
+impl FieldLayout {
+    // nothing in the way
+    pub fn open() -> Self {
+        FieldLayout::default()
+    }
+
+    // a few pillars standing between the rings
+    pub fn pillars() -> Self {
+        const DISTANCE: f32 = (FielderRing::INFIELD_RADIUS + FielderRing::OUTFIELD_RADIUS) / 2.;
+        FieldLayout {
+            obstacles: (0..4)
+                .map(|index| {
+                    let angle = std::f32::consts::FRAC_PI_4
+                        + std::f32::consts::FRAC_PI_2 * index as f32;
+                    Obstacle::new(ObstacleKind::Pillar, 12., DISTANCE, angle)
+                })
+                .collect(),
+        }
+    }
+
+    // bumpers circling the infield, one of which pays out a run,
+    // with rough patches in the outfield
+    pub fn pinball() -> Self {
+        const BUMPER_DISTANCE: f32 = FielderRing::INFIELD_RADIUS * 0.6;
+        const ROUGH_DISTANCE: f32 =
+            (FielderRing::INFIELD_RADIUS + FielderRing::OUTFIELD_RADIUS) / 2.;
+        let orbit_speed = std::f32::consts::FRAC_PI_8;
+        FieldLayout {
+            obstacles: vec![
+                Obstacle::new(ObstacleKind::Bumper, 10., BUMPER_DISTANCE, 0.)
+                    .orbiting(orbit_speed)
+                    .with_bonus(1),
+                Obstacle::new(
+                    ObstacleKind::Bumper,
+                    10.,
+                    BUMPER_DISTANCE,
+                    std::f32::consts::PI * 2. / 3.,
+                )
+                .orbiting(orbit_speed),
+                Obstacle::new(
+                    ObstacleKind::Bumper,
+                    10.,
+                    BUMPER_DISTANCE,
+                    std::f32::consts::PI * 4. / 3.,
+                )
+                .orbiting(orbit_speed),
+                Obstacle::new(
+                    ObstacleKind::SlowZone,
+                    30.,
+                    ROUGH_DISTANCE,
+                    std::f32::consts::FRAC_PI_2,
+                ),
+                Obstacle::new(
+                    ObstacleKind::SlowZone,
+                    30.,
+                    ROUGH_DISTANCE,
+                    -std::f32::consts::FRAC_PI_2,
+                ),
+            ],
+        }
+    }
+}
@@ -1,8 +1,32 @@
 mod objects;
-pub use objects::{ball, batter, fielder};
+pub use objects::{ball, batter, fielder, obstacle, power_up};
 
 mod player;
-pub use player::{Identity, PlayerOne, PlayerTwo, Position, Score};
+pub use player::{Bot, Identity, PlayerOne, PlayerTwo, Position, Remote, Score};
+
+mod clock;
+pub use clock::{BallCountdown, ExtraBallTimer, GustClock, PowerUpTimer, ShotClock};
+
+mod conditions;
+pub use conditions::{Conditions, Weather, WindVane};
+
+mod ground;
+pub use ground::GroundShape;
+
+mod layout;
+pub use layout::FieldLayout;
 
 mod overs;
-pub use overs::{BowlResult, BowlScore, Over};
+pub use overs::{BowlResult, BowlScore, Over, PendingDelivery};
+
+mod pause;
+pub use pause::{Forfeit, MatchPause};
+
+mod rules;
+pub use rules::{
+    FatigueRules, FieldingRestriction, MatchRules, MultiBallRules, PitchRules, PowerUpRules,
+    PowerplayRules, ShotClockPenalty, ShotClockRules,
+};
+
+mod surface;
+pub use surface::{Surface, SurfaceZone};
@@ -1,8 +1,82 @@
 use bevy_ecs::prelude::Component;
 
-#[derive(Component)]
-pub struct Ball;
+use crate::Position;
+
+#[derive(Component, Clone, Default)]
+pub struct Ball {
+    pub kind: BallKind,
+    // passes between fielders since this ball was bowled
+    pub passes: u8,
+    // extra balls are released on top of the delivery in multi-ball matches,
+    // and only award bonus points
+    pub extra: bool,
+    // the side that last hit the ball, who collects any power-ups it passes through
+    pub last_touch: Option<Position>,
+}
 
 impl Ball {
     pub const RADIUS: f32 = 8.;
+
+    pub const fn new(kind: BallKind) -> Self {
+        Ball {
+            kind,
+            passes: 0,
+            extra: false,
+            last_touch: None,
+        }
+    }
+}
+
+// The kind of ball a match is played with.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BallKind {
+    // the standard ball
+    #[default]
+    Leather,
+    // light and lively, for casual games
+    Tennis,
+    // slow to get moving, but hard to stop
+    Heavy,
+    // small and springy
+    Bouncy,
+}
+
+impl BallKind {
+    pub const fn radius(&self) -> f32 {
+        match self {
+            BallKind::Leather => Ball::RADIUS,
+            BallKind::Tennis => 10.,
+            BallKind::Heavy => 9.,
+            BallKind::Bouncy => 7.,
+        }
+    }
+
+    pub const fn mass(&self) -> f32 {
+        match self {
+            BallKind::Leather => 5.,
+            BallKind::Tennis => 2.5,
+            BallKind::Heavy => 10.,
+            BallKind::Bouncy => 4.,
+        }
+    }
+
+    pub const fn restitution(&self) -> f32 {
+        match self {
+            BallKind::Leather => 0.,
+            BallKind::Tennis => 0.8,
+            BallKind::Heavy => 0.,
+            BallKind::Bouncy => 1.,
+        }
+    }
+
+    // scales the damping of the surface the ball is rolling over
+    pub const fn drag(&self) -> f32 {
+        match self {
+            BallKind::Leather => 1.,
+            BallKind::Tennis => 1.5,
+            BallKind::Heavy => 0.6,
+            BallKind::Bouncy => 0.8,
+        }
+    }
 }
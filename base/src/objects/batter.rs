@@ -1,8 +1,24 @@
 use bevy_ecs::prelude::Component;
 
-#[derive(Component, Default)]
+#[derive(Component, Clone)]
 pub struct Batter {
     pub swing_timer: Option<f32>,
+    // how long the current swing has been wound up for, if one is being charged
+    pub charge: Option<f32>,
+    // time left before the batter can swing again after the last swing finished
+    pub recovery_timer: Option<f32>,
+    pub stamina: f32,
+}
+
+impl Default for Batter {
+    fn default() -> Self {
+        Batter {
+            swing_timer: None,
+            charge: None,
+            recovery_timer: None,
+            stamina: Self::MAX_STAMINA,
+        }
+    }
 }
 
 impl Batter {
@@ -13,11 +29,222 @@ impl Batter {
     pub const HWIDTH: f32 = 25.;
     pub const HDEPTH: f32 = 5.;
     pub const MASS: f32 = 50.;
+    // an uncharged swing still moves the bat a little
+    pub const MIN_SWING_POWER: f32 = 0.4;
+    pub const FULL_CHARGE_TIME: f32 = 0.6;
+    // holding the swing past this point starts to lose power
+    pub const OVERCHARGE_TIME: f32 = 1.2;
+    pub const OVERCHARGE_SWING_POWER: f32 = 0.6;
+    pub const STEP_SPEED: f32 = 40.;
+    // how far the batter can step towards and away from the wicket
+    pub const STEP_IN_LIMIT: f32 = 10.;
+    pub const STEP_OUT_LIMIT: f32 = 40.;
+    // stepping further out than this leaves the wicket exposed
+    pub const EXPOSED_STEP: f32 = 20.;
+    pub const MAX_STAMINA: f32 = 100.;
+    pub const SWING_STAMINA_COST: f32 = 20.;
+    // stamina regained while the next ball is being readied
+    pub const DELIVERY_STAMINA_RECOVERY: f32 = 30.;
+    // below this much stamina, swings start to lose power
+    pub const LOW_STAMINA: f32 = 40.;
+    pub const EXHAUSTED_SWING_POWER: f32 = 0.5;
+
+    // the fraction of the bat's swing velocity that a swing charged for `charge` seconds will reach
+    pub fn swing_power(charge: f32) -> f32 {
+        if charge <= Self::FULL_CHARGE_TIME {
+            let progress = (charge / Self::FULL_CHARGE_TIME).max(0.);
+            Self::MIN_SWING_POWER + (1. - Self::MIN_SWING_POWER) * progress
+        } else if charge <= Self::OVERCHARGE_TIME {
+            1.
+        } else {
+            let penalty = ((charge - Self::OVERCHARGE_TIME) / Self::FULL_CHARGE_TIME).min(1.);
+            1. - (1. - Self::OVERCHARGE_SWING_POWER) * penalty
+        }
+    }
+
+    // the fraction of the bat's swing velocity the batter can reach with their current stamina
+    pub fn stamina_power(&self) -> f32 {
+        if self.stamina >= Self::LOW_STAMINA {
+            1.
+        } else {
+            let progress = (self.stamina / Self::LOW_STAMINA).max(0.);
+            Self::EXHAUSTED_SWING_POWER + (1. - Self::EXHAUSTED_SWING_POWER) * progress
+        }
+    }
+
+    pub fn is_tired(&self) -> bool {
+        self.stamina < Self::LOW_STAMINA
+    }
+
+    pub fn spend_stamina(&mut self) {
+        self.stamina = (self.stamina - Self::SWING_STAMINA_COST).max(0.);
+    }
+
+    pub fn recover_stamina(&mut self) {
+        self.stamina = (self.stamina + Self::DELIVERY_STAMINA_RECOVERY).min(Self::MAX_STAMINA);
+    }
+
+    pub fn is_overcharged(&self) -> bool {
+        self.charge
+            .is_some_and(|charge| charge > Self::OVERCHARGE_TIME)
+    }
 }
 
-#[derive(Component)]
-pub struct Wicket;
+// The stats of the bat being swung.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bat {
+    pub hwidth: f32,
+    pub hdepth: f32,
+    pub mass: f32,
+    pub swing_velocity: f32,
+    pub swing_time: f32,
+}
+
+impl Bat {
+    pub const fn standard() -> Self {
+        Bat {
+            hwidth: Batter::HWIDTH,
+            hdepth: Batter::HDEPTH,
+            mass: Batter::MASS,
+            swing_velocity: Batter::SWING_VELOCITY,
+            swing_time: Batter::SWING_TIME,
+        }
+    }
+
+    // covers more of the ring, but is slow to get around
+    pub const fn wide() -> Self {
+        Bat {
+            hwidth: 32.,
+            hdepth: 6.,
+            mass: 70.,
+            swing_velocity: std::f32::consts::PI * 1.5,
+            swing_time: 0.35,
+        }
+    }
+
+    // leaves gaps, but whips around quickly
+    pub const fn narrow() -> Self {
+        Bat {
+            hwidth: 18.,
+            hdepth: 4.,
+            mass: 35.,
+            swing_velocity: std::f32::consts::PI * 2.6,
+            swing_time: 0.25,
+        }
+    }
+
+    // distance from the wicket to the middle of the bat while standing in the crease
+    pub fn crease_radius(&self) -> f32 {
+        Batter::RADIUS + self.hwidth
+    }
+
+    pub fn min_step_radius(&self) -> f32 {
+        self.crease_radius() - Batter::STEP_IN_LIMIT
+    }
+
+    pub fn max_step_radius(&self) -> f32 {
+        self.crease_radius() + Batter::STEP_OUT_LIMIT
+    }
+
+    pub fn exposed_step_radius(&self) -> f32 {
+        self.crease_radius() + Batter::EXPOSED_STEP
+    }
+}
+
+impl Default for Bat {
+    fn default() -> Self {
+        Bat::standard()
+    }
+}
+
+// The bat a player has picked for the match.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub enum BatKind {
+    #[default]
+    Standard,
+    Wide,
+    Narrow,
+}
+
+impl BatKind {
+    pub const ALL: [BatKind; 3] = [BatKind::Standard, BatKind::Wide, BatKind::Narrow];
+
+    pub const fn bat(&self) -> Bat {
+        match self {
+            BatKind::Standard => Bat::standard(),
+            BatKind::Wide => Bat::wide(),
+            BatKind::Narrow => Bat::narrow(),
+        }
+    }
+}
+
+impl std::fmt::Display for BatKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Component, Clone, Default)]
+pub struct Wicket {
+    // whether the batter has stepped far enough out of the crease to be stumped
+    pub exposed: bool,
+}
 
 impl Wicket {
     pub const RADIUS: f32 = Batter::RADIUS - crate::ball::Ball::RADIUS * 2.;
+    pub const EXPOSED_RADIUS: f32 = Batter::RADIUS;
+
+    pub const fn radius(&self) -> f32 {
+        if self.exposed {
+            Self::EXPOSED_RADIUS
+        } else {
+            Self::RADIUS
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn charging_builds_swing_power_until_overcharged() {
+        assert_eq!(Batter::swing_power(0.), Batter::MIN_SWING_POWER);
+        assert!(Batter::swing_power(Batter::FULL_CHARGE_TIME / 2.) < 1.);
+        assert_eq!(Batter::swing_power(Batter::FULL_CHARGE_TIME), 1.);
+        assert_eq!(Batter::swing_power(Batter::OVERCHARGE_TIME), 1.);
+        assert!(Batter::swing_power(Batter::OVERCHARGE_TIME + 0.1) < 1.);
+        // holding on for ever only loses so much
+        assert_eq!(
+            Batter::swing_power(Batter::OVERCHARGE_TIME * 10.),
+            Batter::OVERCHARGE_SWING_POWER
+        );
+    }
+
+    #[test]
+    fn tired_batters_swing_slower() {
+        let mut batter = Batter::default();
+        assert_eq!(batter.stamina_power(), 1.);
+        for _ in 0..4 {
+            batter.spend_stamina();
+        }
+        assert_eq!(
+            batter.stamina,
+            Batter::MAX_STAMINA - Batter::SWING_STAMINA_COST * 4.
+        );
+        assert!(batter.is_tired());
+        assert!(batter.stamina_power() < 1.);
+        for _ in 0..10 {
+            batter.spend_stamina();
+        }
+        assert_eq!(batter.stamina_power(), Batter::EXHAUSTED_SWING_POWER);
+        // a rest between balls helps, but can't go beyond full stamina
+        batter.recover_stamina();
+        assert_eq!(batter.stamina, Batter::DELIVERY_STAMINA_RECOVERY);
+        for _ in 0..10 {
+            batter.recover_stamina();
+        }
+        assert_eq!(batter.stamina, Batter::MAX_STAMINA);
+    }
 }
@@ -1,7 +1,10 @@
-use bevy_ecs::prelude::Component;
+use bevy_ecs::prelude::{Component, Resource};
 use bevy_math::{Quat, Vec3};
 
+use crate::GroundShape;
+
 #[derive(Clone, Copy, Component, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FielderRing {
     Infield,
     Outfield,
@@ -20,6 +23,7 @@ impl FielderRing {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FielderPosition {
     Top,
     Bottom,
@@ -28,31 +32,35 @@ pub enum FielderPosition {
 }
 
 impl FielderPosition {
-    // starting rotation
-    fn rotation(&self) -> Quat {
-        match self {
-            FielderPosition::Top => Quat::from_rotation_z(0.),
-            FielderPosition::Bottom => Quat::from_rotation_z(std::f32::consts::PI),
-            FielderPosition::Left => Quat::from_rotation_z(std::f32::consts::FRAC_PI_2),
-            FielderPosition::Right => Quat::from_rotation_z(-std::f32::consts::FRAC_PI_2),
-        }
-    }
-
-    // starting translation
-    fn translation(&self, radius: f32) -> Vec3 {
+    // in the order fielders are posted to a ring that can't be fully manned
+    pub const ALL: [FielderPosition; 4] = [
+        FielderPosition::Top,
+        FielderPosition::Bottom,
+        FielderPosition::Left,
+        FielderPosition::Right,
+    ];
+
+    // starting angle around the ring, counter-clockwise from the top
+    pub const fn angle(&self) -> f32 {
         match self {
-            FielderPosition::Top => Vec3::new(0., radius, 1.),
-            FielderPosition::Bottom => Vec3::new(0., -radius, 1.),
-            FielderPosition::Left => Vec3::new(-radius, 0., 1.),
-            FielderPosition::Right => Vec3::new(radius, 0., 1.),
+            FielderPosition::Top => 0.,
+            FielderPosition::Bottom => std::f32::consts::PI,
+            FielderPosition::Left => std::f32::consts::FRAC_PI_2,
+            FielderPosition::Right => -std::f32::consts::FRAC_PI_2,
         }
     }
 }
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct Fielder {
     pub position: FielderPosition,
     pub ring: FielderRing,
+    // the fraction of rotation speed lost to sustained movement
+    pub fatigue: f32,
+    // how far around the ring the fielder has travelled, counter-clockwise from the top
+    pub angle: f32,
+    // how much wider than usual the fielder's paddle is
+    pub reach: f32,
 }
 
 impl Fielder {
@@ -63,15 +71,26 @@ impl Fielder {
     pub const OUTFIELD_HWIDTH: f32 = 50.;
     pub const HDEPTH: f32 = 2.;
 
-    const fn new(position: FielderPosition, ring: FielderRing) -> Self {
-        Fielder { position, ring }
+    pub const fn new(position: FielderPosition, ring: FielderRing) -> Self {
+        Fielder {
+            position,
+            ring,
+            fatigue: 0.,
+            angle: position.angle(),
+            reach: 1.,
+        }
+    }
+
+    pub fn rotation_speed(&self) -> f32 {
+        Self::ROTATION_SPEED * (1. - self.fatigue)
     }
 
-    pub const fn hwidth(&self) -> f32 {
-        match self.ring {
+    pub fn hwidth(&self) -> f32 {
+        let hwidth = match self.ring {
             FielderRing::Infield => Self::INFIELD_HWIDTH,
             FielderRing::Outfield => Self::OUTFIELD_HWIDTH,
-        }
+        };
+        hwidth * self.reach
     }
 
     pub const fn top(ring: FielderRing) -> Self {
@@ -90,16 +109,23 @@ impl Fielder {
         Fielder::new(FielderPosition::Right, ring)
     }
 
-    pub fn rotation(&self) -> Quat {
-        self.position.rotation()
+    // fielders face the middle of the ground wherever they are on their ring
+    pub fn rotation(&self, ground: &GroundShape) -> Quat {
+        ground.facing(self.ring.radius(), self.angle)
     }
 
-    pub fn translation(&self) -> Vec3 {
-        self.position.translation(self.ring.radius())
+    pub fn translation(&self, ground: &GroundShape) -> Vec3 {
+        ground.point(self.ring.radius(), self.angle).extend(1.)
     }
 }
 
-#[derive(Component)]
+// How far the outfield has turned from its starting positions.
+// This outlasts the outfielders themselves, so that a ring emptied by a powerplay
+// is manned again where it was left.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct OutfieldRotation(pub f32);
+
+#[derive(Component, Clone)]
 pub struct Boundary;
 
 impl Boundary {
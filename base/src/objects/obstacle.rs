@@ -0,0 +1,73 @@
+use bevy_ecs::prelude::Component;
+use bevy_math::Vec2;
+
+use crate::GroundShape;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ObstacleKind {
+    // a solid post that soaks up some of the ball's speed
+    Pillar,
+    // a springy post that sends the ball back the way it came
+    Bumper,
+    // a patch of rough ground that the ball rolls through, losing speed
+    SlowZone,
+}
+
+impl ObstacleKind {
+    pub const fn restitution(&self) -> f32 {
+        match self {
+            ObstacleKind::Pillar => 0.3,
+            ObstacleKind::Bumper => 1.,
+            ObstacleKind::SlowZone => 0.,
+        }
+    }
+
+    pub const fn is_sensor(&self) -> bool {
+        matches!(self, ObstacleKind::SlowZone)
+    }
+}
+
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct Obstacle {
+    pub kind: ObstacleKind,
+    pub radius: f32,
+    // nominal distance from the wicket, following the shape of the ground,
+    // and angle from the top of the field (counter-clockwise)
+    pub distance: f32,
+    pub angle: f32,
+    // if set, the obstacle circles the wicket at this many radians per second
+    pub orbit_speed: Option<f32>,
+    // runs awarded to the batter every time the ball touches this obstacle
+    pub bonus_runs: Option<u16>,
+}
+
+impl Obstacle {
+    pub const SLOW_ZONE_DAMPING: f32 = 3.;
+    pub const MASS: f32 = 100.;
+
+    pub const fn new(kind: ObstacleKind, radius: f32, distance: f32, angle: f32) -> Self {
+        Obstacle {
+            kind,
+            radius,
+            distance,
+            angle,
+            orbit_speed: None,
+            bonus_runs: None,
+        }
+    }
+
+    pub const fn orbiting(mut self, orbit_speed: f32) -> Self {
+        self.orbit_speed = Some(orbit_speed);
+        self
+    }
+
+    pub const fn with_bonus(mut self, runs: u16) -> Self {
+        self.bonus_runs = Some(runs);
+        self
+    }
+
+    // starting position on the field
+    pub fn translation(&self, ground: &GroundShape) -> Vec2 {
+        ground.point(self.distance, self.angle)
+    }
+}
@@ -0,0 +1,81 @@
+use bevy_ecs::prelude::Component;
+
+use crate::Position;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PowerUpKind {
+    // widens the paddles of whoever picks it up
+    WideBat,
+    // drags on every ball in play
+    SlowBall,
+    // stops the other side from moving: the batter around the crease, or the infield ring
+    FrozenRing,
+    // doubles every run scored by whoever picks it up
+    DoubleRuns,
+}
+
+impl PowerUpKind {
+    pub const ALL: [PowerUpKind; 4] = [
+        PowerUpKind::WideBat,
+        PowerUpKind::SlowBall,
+        PowerUpKind::FrozenRing,
+        PowerUpKind::DoubleRuns,
+    ];
+
+    // how long the effect lasts once picked up, in seconds
+    pub const fn duration(&self) -> f32 {
+        match self {
+            PowerUpKind::WideBat => 8.,
+            PowerUpKind::SlowBall => 5.,
+            PowerUpKind::FrozenRing => 3.,
+            PowerUpKind::DoubleRuns => 10.,
+        }
+    }
+}
+
+impl std::fmt::Display for PowerUpKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PowerUpKind::WideBat => write!(f, "Wide bat"),
+            PowerUpKind::SlowBall => write!(f, "Slow ball"),
+            PowerUpKind::FrozenRing => write!(f, "Frozen ring"),
+            PowerUpKind::DoubleRuns => write!(f, "Double runs"),
+        }
+    }
+}
+
+// A pickup waiting on the field for the ball to pass through it.
+#[derive(Component, Clone)]
+pub struct PowerUp {
+    pub kind: PowerUpKind,
+    // seconds left before the pickup disappears uncollected
+    pub lifetime: f32,
+}
+
+impl PowerUp {
+    pub const RADIUS: f32 = 12.;
+    pub const WIDE_BAT_SCALE: f32 = 1.5;
+    pub const SLOW_BALL_DAMPING: f32 = 1.5;
+
+    pub const fn new(kind: PowerUpKind, lifetime: f32) -> Self {
+        PowerUp { kind, lifetime }
+    }
+}
+
+// An effect granted to one side by a collected pickup, until it runs out.
+#[derive(Component, Clone)]
+pub struct PowerUpEffect {
+    pub kind: PowerUpKind,
+    pub side: Position,
+    pub remaining: f32,
+}
+
+impl PowerUpEffect {
+    pub const fn new(kind: PowerUpKind, side: Position) -> Self {
+        PowerUpEffect {
+            kind,
+            side,
+            remaining: kind.duration(),
+        }
+    }
+}
@@ -2,13 +2,20 @@ use bevy_ecs::prelude::Resource;
 
 use crate::Identity;
 
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BowlScore {
     pub scorer: Identity,
     pub value: u16,
 }
 
+// The score of the ball in play, which goes into the over once every ball is resolved.
+#[derive(Resource, Clone, Default)]
+pub struct PendingDelivery(pub Option<BowlScore>);
+
 // AKA an "inning"
-#[derive(Resource, Default)]
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Over(Vec<BowlScore>);
 
 pub enum BowlResult {
@@ -18,17 +25,28 @@ pub enum BowlResult {
 }
 
 impl Over {
+    pub const LENGTH: usize = 6;
+    pub const GAME_LENGTH: usize = Self::LENGTH * 2;
+
     pub fn get(&self, index: usize) -> Option<&BowlScore> {
         self.0.get(index)
     }
 
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     pub fn push(&mut self, score: BowlScore) -> BowlResult {
         self.0.push(score);
         match self.0.len() {
             // switch sides after one over
-            6 => BowlResult::ChangePositions,
+            Self::LENGTH => BowlResult::ChangePositions,
             // end the game after two
-            12 => BowlResult::GameOver,
+            Self::GAME_LENGTH => BowlResult::GameOver,
             _ => BowlResult::None,
         }
     }
@@ -0,0 +1,36 @@
+use bevy_ecs::{prelude::Resource, schedule::States};
+
+use crate::Identity;
+
+// Holds the match still in whatever phase it is in, such as while a networked player is
+// missing. Nothing in the match moves on while it is paused, and it carries on from the
+// same place once it is running again.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, States)]
+pub enum MatchPause {
+    #[default]
+    Running,
+    Paused,
+}
+
+// The player who gave up the match by leaving it, if anyone did.
+// The other player wins, whatever the score.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct Forfeit(pub Option<Identity>);
+
+impl Forfeit {
+    pub fn winner(&self) -> Option<Identity> {
+        self.0.map(|player| !player)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_player_who_stays_wins_a_forfeited_match() {
+        assert_eq!(Forfeit::default().winner(), None);
+        assert_eq!(Forfeit(Some(Identity::One)).winner(), Some(Identity::Two));
+        assert_eq!(Forfeit(Some(Identity::Two)).winner(), Some(Identity::One));
+    }
+}
@@ -3,6 +3,7 @@ use std::fmt::Display;
 use bevy_ecs::prelude::Component;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Identity {
     One,
     Two,
@@ -14,6 +15,28 @@ impl Display for Identity {
     }
 }
 
+impl Identity {
+    // players are told apart by which of the PlayerOne and PlayerTwo markers they carry
+    pub const fn of(is_player_one: bool) -> Self {
+        if is_player_one {
+            Identity::One
+        } else {
+            Identity::Two
+        }
+    }
+}
+
+impl std::ops::Not for Identity {
+    type Output = Identity;
+
+    fn not(self) -> Self::Output {
+        match self {
+            Identity::One => Identity::Two,
+            Identity::Two => Identity::One,
+        }
+    }
+}
+
 impl TryFrom<u8> for Identity {
     type Error = String;
 
@@ -26,7 +49,12 @@ impl TryFrom<u8> for Identity {
     }
 }
 
+// A player whose actions are chosen by the computer.
+#[derive(Component)]
+pub struct Bot;
+
 #[derive(Clone, Copy, Debug, PartialEq, Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Position {
     Fielder,
     Batter,
@@ -49,11 +77,15 @@ impl Display for Position {
     }
 }
 
+// A player who is playing from another machine, so has no controls on this one.
 #[derive(Component)]
+pub struct Remote;
+
+#[derive(Component, Clone)]
 pub struct Score(pub u16);
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct PlayerOne;
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct PlayerTwo;
@@ -0,0 +1,271 @@
+use bevy_ecs::prelude::Resource;
+
+use crate::{
+    ball::BallKind, fielder::FielderPosition, FieldLayout, GroundShape, Over, Surface, Weather,
+};
+
+// Tunable rules for a match.
+// Insert this resource before adding the GameplayPlugin to override the defaults.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct MatchRules {
+    // if set, the batter must wait this long after a swing before swinging again
+    pub swing_recovery_time: Option<f32>,
+    pub fatigue: Option<FatigueRules>,
+    pub pitch: Option<PitchRules>,
+    pub weather: Weather,
+    pub surface: Surface,
+    pub ball: BallKind,
+    pub ground: GroundShape,
+    pub layout: FieldLayout,
+    // if set, extra balls are released while the ball is in play
+    pub multi_ball: Option<MultiBallRules>,
+    // if set, power-ups appear on the field while the ball is in play
+    pub power_ups: Option<PowerUpRules>,
+    // if set, the outfield is restricted for the first few balls of each innings
+    pub powerplay: Option<PowerplayRules>,
+    // seconds to wait after each ball before the next can be bowled
+    pub pause: Option<f32>,
+    // if set, both sides must also press ready before the next ball
+    pub ready_check: bool,
+    // if set, the fielding side must bowl within a time limit
+    pub shot_clock: Option<ShotClockRules>,
+    // seeds every random effect in the match, so that matches can be replayed
+    pub seed: u64,
+}
+
+// Chaos mode: more balls are released at intervals while the delivery is in play,
+// and the delivery only ends once every ball has been scored.
+#[derive(Clone, Debug)]
+pub struct MultiBallRules {
+    // seconds between the release of each extra ball
+    pub interval: f32,
+    // the most balls that can be in play at once, including the delivery
+    pub max_balls: usize,
+}
+
+impl Default for MultiBallRules {
+    fn default() -> Self {
+        MultiBallRules {
+            interval: 3.,
+            max_balls: 4,
+        }
+    }
+}
+
+impl MatchRules {
+    pub fn is_powerplay(&self, over: &Over) -> bool {
+        self.powerplay
+            .as_ref()
+            .is_some_and(|powerplay| powerplay.is_active(over))
+    }
+
+    pub fn locks_outfield(&self, over: &Over) -> bool {
+        self.powerplay
+            .as_ref()
+            .is_some_and(|powerplay| powerplay.locks_outfield(over))
+    }
+
+    pub fn outfielders(&self, over: &Over) -> usize {
+        self.powerplay
+            .as_ref()
+            .map_or(FielderPosition::ALL.len(), |powerplay| {
+                powerplay.outfielders(over)
+            })
+    }
+}
+
+// What happens when the fielding side lets the shot clock run out.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ShotClockPenalty {
+    // the ball is bowled for them
+    #[default]
+    AutoBowl,
+    // the batter is given a run, and the clock starts again
+    PenaltyRun,
+}
+
+#[derive(Clone, Debug)]
+pub struct ShotClockRules {
+    // seconds the fielding side has to bowl each ball
+    pub seconds: f32,
+    pub penalty: ShotClockPenalty,
+}
+
+impl Default for ShotClockRules {
+    fn default() -> Self {
+        ShotClockRules {
+            seconds: 10.,
+            penalty: ShotClockPenalty::default(),
+        }
+    }
+}
+
+// How the outfield ring is held back during a powerplay.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FieldingRestriction {
+    // the outfield can't move at all
+    Locked,
+    // only this many outfielders take the field
+    Fielders(usize),
+}
+
+// Cricket-style fielding restrictions for the first balls of each innings.
+#[derive(Clone, Debug)]
+pub struct PowerplayRules {
+    pub balls: usize,
+    pub restriction: FieldingRestriction,
+}
+
+impl PowerplayRules {
+    pub fn is_active(&self, over: &Over) -> bool {
+        over.len() % Over::LENGTH < self.balls
+    }
+
+    pub fn locks_outfield(&self, over: &Over) -> bool {
+        self.restriction == FieldingRestriction::Locked && self.is_active(over)
+    }
+
+    // how many outfielders may take the field for the next ball
+    pub fn outfielders(&self, over: &Over) -> usize {
+        match self.restriction {
+            FieldingRestriction::Fielders(count) if self.is_active(over) => {
+                count.min(FielderPosition::ALL.len())
+            }
+            _ => FielderPosition::ALL.len(),
+        }
+    }
+}
+
+impl Default for PowerplayRules {
+    fn default() -> Self {
+        PowerplayRules {
+            balls: 2,
+            restriction: FieldingRestriction::Locked,
+        }
+    }
+}
+
+// Pickups appear at random spots on the field, granting timed effects to whichever
+// side last touched the ball that passes through them.
+#[derive(Clone, Debug)]
+pub struct PowerUpRules {
+    // seconds between each new pickup
+    pub interval: f32,
+    // the most pickups that can be waiting on the field at once
+    pub max_pickups: usize,
+    // seconds a pickup waits to be collected before disappearing
+    pub lifetime: f32,
+}
+
+impl Default for PowerUpRules {
+    fn default() -> Self {
+        PowerUpRules {
+            interval: 5.,
+            max_pickups: 2,
+            lifetime: 8.,
+        }
+    }
+}
+
+// Fielders slow down the longer they keep moving, and rest between overs.
+#[derive(Clone, Debug)]
+pub struct FatigueRules {
+    // fraction of rotation speed lost per second spent moving
+    pub rate: f32,
+    // the most rotation speed a fielder can lose
+    pub max_fatigue: f32,
+}
+
+impl Default for FatigueRules {
+    fn default() -> Self {
+        FatigueRules {
+            rate: 0.02,
+            max_fatigue: 0.3,
+        }
+    }
+}
+
+// The pitch wears down as more balls are bowled on it.
+#[derive(Clone, Debug)]
+pub struct PitchRules {
+    // the pitch starts to wear once this many balls have been bowled
+    pub wear_after: usize,
+    // linear damping added to the ball for each ball bowled on a worn pitch
+    pub drag_per_ball: f32,
+    // largest random deflection (in radians) when the ball bounces off a fielder,
+    // for each ball bowled on a worn pitch
+    pub deflection_per_ball: f32,
+}
+
+impl Default for PitchRules {
+    fn default() -> Self {
+        PitchRules {
+            wear_after: Over::LENGTH,
+            drag_per_ball: 0.02,
+            deflection_per_ball: 0.02,
+        }
+    }
+}
+
+impl PitchRules {
+    // how many balls have been bowled on a worn pitch
+    pub fn wear(&self, over: &Over) -> f32 {
+        over.len().saturating_sub(self.wear_after) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BowlScore, Identity};
+
+    fn over_of(balls: usize) -> Over {
+        let mut over = Over::default();
+        for _ in 0..balls {
+            over.push(BowlScore {
+                scorer: Identity::One,
+                value: 1,
+            });
+        }
+        over
+    }
+
+    #[test]
+    fn powerplays_restrict_the_outfield_at_the_start_of_each_innings() {
+        let rules = MatchRules {
+            powerplay: Some(PowerplayRules {
+                balls: 2,
+                restriction: FieldingRestriction::Fielders(1),
+            }),
+            ..Default::default()
+        };
+        let outfielders = (0..Over::GAME_LENGTH)
+            .map(|balls| rules.outfielders(&over_of(balls)))
+            .collect::<Vec<_>>();
+        assert_eq!(outfielders, [1, 1, 4, 4, 4, 4, 1, 1, 4, 4, 4, 4]);
+        assert!(!rules.locks_outfield(&Over::default()));
+    }
+
+    #[test]
+    fn locked_outfields_keep_every_fielder() {
+        let rules = MatchRules {
+            powerplay: Some(PowerplayRules::default()),
+            ..Default::default()
+        };
+        assert!(rules.locks_outfield(&Over::default()));
+        assert!(!rules.locks_outfield(&over_of(2)));
+        assert_eq!(
+            rules.outfielders(&Over::default()),
+            FielderPosition::ALL.len()
+        );
+        // more fielders than there are positions is as many as there are positions
+        let generous = PowerplayRules {
+            balls: 2,
+            restriction: FieldingRestriction::Fielders(10),
+        };
+        assert_eq!(
+            generous.outfielders(&Over::default()),
+            FielderPosition::ALL.len()
+        );
+    }
+}
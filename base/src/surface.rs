@@ -0,0 +1,74 @@
+use crate::fielder::FielderRing;
+
+// How quickly the ball loses speed and spin while rolling over part of the field.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SurfaceZone {
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+}
+
+// The playing surface, with separate zones inside and outside the infield ring.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Surface {
+    pub infield: SurfaceZone,
+    pub outfield: SurfaceZone,
+}
+
+impl Surface {
+    // a quick, well-kept surface that barely slows the ball
+    pub fn fast() -> Self {
+        Surface {
+            infield: SurfaceZone {
+                linear_damping: 0.05,
+                angular_damping: 0.2,
+            },
+            outfield: SurfaceZone {
+                linear_damping: 0.1,
+                angular_damping: 0.2,
+            },
+        }
+    }
+
+    // long outfield grass that takes the pace off big hits
+    pub fn slow() -> Self {
+        Surface {
+            infield: SurfaceZone {
+                linear_damping: 0.15,
+                angular_damping: 0.5,
+            },
+            outfield: SurfaceZone {
+                linear_damping: 0.4,
+                angular_damping: 0.5,
+            },
+        }
+    }
+
+    // a soft infield that grips the ball and kills its spin
+    pub fn sticky() -> Self {
+        Surface {
+            infield: SurfaceZone {
+                linear_damping: 0.5,
+                angular_damping: 2.,
+            },
+            outfield: SurfaceZone {
+                linear_damping: 0.2,
+                angular_damping: 1.,
+            },
+        }
+    }
+
+    // the zone covering points on the ground's curve of nominal size `radius`
+    pub fn zone_at(&self, radius: f32) -> &SurfaceZone {
+        if radius <= FielderRing::INFIELD_RADIUS {
+            &self.infield
+        } else {
+            &self.outfield
+        }
+    }
+}
+
+impl Default for Surface {
+    fn default() -> Self {
+        Surface::fast()
+    }
+}
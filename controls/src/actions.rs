@@ -9,6 +9,15 @@ pub enum BatterControl {
     SwingCCW,
     MoveCW,
     MoveCCW,
+    StepIn,
+    StepOut,
+    Ready,
+}
+
+impl BatterControl {
+    pub fn is_swing(&self) -> bool {
+        matches!(self, BatterControl::SwingCW | BatterControl::SwingCCW)
+    }
 }
 
 impl From<BatterControl> for BatterAction {
@@ -18,6 +27,9 @@ impl From<BatterControl> for BatterAction {
             BatterControl::SwingCCW => BatterAction::SwingCCW,
             BatterControl::MoveCW => BatterAction::MoveCW,
             BatterControl::MoveCCW => BatterAction::MoveCCW,
+            BatterControl::StepIn => BatterAction::StepIn,
+            BatterControl::StepOut => BatterAction::StepOut,
+            BatterControl::Ready => BatterAction::Ready,
         }
     }
 }
@@ -29,6 +41,7 @@ pub enum FielderControl {
     MoveInfieldCCW,
     MoveOutfieldCW,
     MoveOutfieldCCW,
+    Ready,
 }
 
 impl From<FielderControl> for FielderAction {
@@ -39,6 +52,7 @@ impl From<FielderControl> for FielderAction {
             FielderControl::MoveInfieldCCW => FielderAction::MoveInfieldCCW,
             FielderControl::MoveOutfieldCW => FielderAction::MoveOutfieldCW,
             FielderControl::MoveOutfieldCCW => FielderAction::MoveOutfieldCCW,
+            FielderControl::Ready => FielderAction::Ready,
         }
     }
 }
@@ -16,6 +16,9 @@ impl BatterControllerBundle {
             (KeyCode::W, BatterControl::SwingCW),
             (KeyCode::A, BatterControl::MoveCCW),
             (KeyCode::S, BatterControl::MoveCW),
+            (KeyCode::Z, BatterControl::StepIn),
+            (KeyCode::X, BatterControl::StepOut),
+            (KeyCode::E, BatterControl::Ready),
         ])
         .build();
         BatterControllerBundle {
@@ -39,6 +42,9 @@ impl BatterControllerBundle2 {
             (KeyCode::I, BatterControl::SwingCW),
             (KeyCode::J, BatterControl::MoveCCW),
             (KeyCode::K, BatterControl::MoveCW),
+            (KeyCode::M, BatterControl::StepIn),
+            (KeyCode::Comma, BatterControl::StepOut),
+            (KeyCode::O, BatterControl::Ready),
         ])
         .build();
         BatterControllerBundle2 {
@@ -63,6 +69,7 @@ impl FielderControllerBundle {
             (KeyCode::W, FielderControl::MoveOutfieldCW),
             (KeyCode::A, FielderControl::MoveInfieldCCW),
             (KeyCode::S, FielderControl::MoveInfieldCW),
+            (KeyCode::E, FielderControl::Ready),
         ])
         .build();
         FielderControllerBundle {
@@ -87,6 +94,7 @@ impl FielderControllerBundle2 {
             (KeyCode::I, FielderControl::MoveOutfieldCW),
             (KeyCode::J, FielderControl::MoveInfieldCCW),
             (KeyCode::K, FielderControl::MoveInfieldCW),
+            (KeyCode::O, FielderControl::Ready),
         ])
         .build();
         FielderControllerBundle2 {
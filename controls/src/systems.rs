@@ -1,10 +1,13 @@
-use bevy::prelude::{Changed, Commands, Entity, Query, ResMut, With, Without};
+use bevy::{
+    ecs::query::Has,
+    prelude::{Changed, Commands, Entity, Query, ResMut, With, Without},
+};
 
 use leafwing_input_manager::{prelude::ActionState, InputManagerBundle};
 
 use cricket_pong_game::{
-    actions::{Action, Actions},
-    base::{PlayerOne, PlayerTwo, Position},
+    actions::{Action, Actions, BatterAction},
+    base::{Bot, Identity, PlayerOne, PlayerTwo, Position, Remote},
 };
 
 use crate::{
@@ -13,37 +16,64 @@ use crate::{
 };
 
 pub(crate) fn queue_inputs(
-    batter_query: Query<&ActionState<BatterControl>>,
-    fielder_query: Query<&ActionState<FielderControl>>,
+    batter_query: Query<(&ActionState<BatterControl>, Has<PlayerOne>)>,
+    fielder_query: Query<(&ActionState<FielderControl>, Has<PlayerOne>)>,
     mut actions: ResMut<Actions>,
 ) {
-    for action_state in batter_query.iter() {
-        let batter_actions = action_state.get_pressed();
-        actions.0.extend(
-            batter_actions
-                .into_iter()
-                .map(|action| Action::Batter(action.into())),
-        );
+    for (action_state, is_player_one) in batter_query.iter() {
+        let player = Identity::of(is_player_one);
+        // swings are wound up while the key is held and let go when it is released
+        let is_charging = action_state.pressed(BatterControl::SwingCW)
+            || action_state.pressed(BatterControl::SwingCCW);
+        if is_charging {
+            actions.push(player, Action::Batter(BatterAction::ChargeSwing));
+        }
+        let batter_actions = action_state
+            .get_pressed()
+            .into_iter()
+            .filter(|action| !action.is_swing())
+            .chain(
+                action_state
+                    .get_just_released()
+                    .into_iter()
+                    .filter(BatterControl::is_swing),
+            );
+        for action in batter_actions {
+            actions.push(player, Action::Batter(action.into()));
+        }
     }
-    for action_state in fielder_query.iter() {
-        let fielder_actions = action_state.get_pressed();
-        actions.0.extend(
-            fielder_actions
-                .into_iter()
-                .map(|action| Action::Fielder(action.into())),
-        );
+    for (action_state, is_player_one) in fielder_query.iter() {
+        let player = Identity::of(is_player_one);
+        for action in action_state.get_pressed() {
+            actions.push(player, Action::Fielder(action.into()));
+        }
     }
 }
 
+// bots choose their own actions and remote players are controlled from their own machines,
+// so only local human players are given controllers
+#[allow(clippy::type_complexity)]
 pub(crate) fn sync_controllers(
     mut commands: Commands,
     player_one_query: Query<
         (Entity, &Position),
-        (With<PlayerOne>, Without<PlayerTwo>, Changed<Position>),
+        (
+            With<PlayerOne>,
+            Without<PlayerTwo>,
+            Without<Bot>,
+            Without<Remote>,
+            Changed<Position>,
+        ),
     >,
     player_two_query: Query<
         (Entity, &Position),
-        (With<PlayerTwo>, Without<PlayerOne>, Changed<Position>),
+        (
+            With<PlayerTwo>,
+            Without<PlayerOne>,
+            Without<Bot>,
+            Without<Remote>,
+            Changed<Position>,
+        ),
     >,
 ) {
     for (entity, position) in player_one_query.iter() {
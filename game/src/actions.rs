@@ -1,11 +1,21 @@
 use bevy_ecs::prelude::Resource;
 
-#[derive(Clone, Copy, Debug)]
+use cricket_pong_base::{Identity, Position};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BatterAction {
+    // the swing key is being held, winding up the next swing
+    ChargeSwing,
+    // the swing key was released, so the wound-up swing is let go
     SwingCW,
     SwingCCW,
     MoveCW,
     MoveCCW,
+    StepIn,
+    StepOut,
+    // ready for the next ball
+    Ready,
 }
 
 impl BatterAction {
@@ -13,17 +23,32 @@ impl BatterAction {
         match self {
             BatterAction::MoveCW | BatterAction::SwingCW => -1.,
             BatterAction::MoveCCW | BatterAction::SwingCCW => 1.,
+            BatterAction::ChargeSwing
+            | BatterAction::StepIn
+            | BatterAction::StepOut
+            | BatterAction::Ready => 0.,
+        }
+    }
+
+    pub fn step_direction(&self) -> f32 {
+        match self {
+            BatterAction::StepIn => -1.,
+            BatterAction::StepOut => 1.,
+            _ => 0.,
         }
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FielderAction {
     Bowl,
     MoveInfieldCW,
     MoveInfieldCCW,
     MoveOutfieldCW,
     MoveOutfieldCCW,
+    // ready for the next ball
+    Ready,
 }
 
 impl FielderAction {
@@ -36,10 +61,35 @@ impl FielderAction {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Action {
     Fielder(FielderAction),
     Batter(BatterAction),
 }
 
+impl Action {
+    // the position a player has to be playing to take this action
+    pub fn position(&self) -> Position {
+        match self {
+            Action::Fielder(_) => Position::Fielder,
+            Action::Batter(_) => Position::Batter,
+        }
+    }
+}
+
+// An action, along with the player who asked for it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QueuedAction {
+    pub player: Identity,
+    pub action: Action,
+}
+
 #[derive(Default, Resource)]
-pub struct Actions(pub Vec<Action>);
+pub struct Actions(pub Vec<QueuedAction>);
+
+impl Actions {
+    pub fn push(&mut self, player: Identity, action: Action) {
+        self.0.push(QueuedAction { player, action });
+    }
+}
@@ -1,20 +1,28 @@
 use bevy_app::prelude::{App, Plugin, Update};
 use bevy_ecs::{
     prelude::{in_state, OnEnter, States, SystemSet},
-    schedule::{Condition, IntoSystemConfigs, OnExit},
+    schedule::{
+        BoxedScheduleLabel, Condition, IntoSystemConfigs, IntoSystemSetConfig,
+        IntoSystemSetConfigs, OnExit, ScheduleLabel,
+    },
 };
 
 use bevy_math::prelude::Vec2;
 
-use bevy_rapier2d::prelude::{RapierConfiguration, RapierPhysicsPlugin};
+use bevy_rapier2d::prelude::{PhysicsSet, RapierConfiguration, RapierPhysicsPlugin};
 
-pub use cricket_pong_base::{self as base, Over};
+pub use cricket_pong_base::{
+    self as base, BallCountdown, Conditions, ExtraBallTimer, Forfeit, GustClock, MatchPause,
+    MatchRules, Over, PendingDelivery, PowerUpTimer, ShotClock,
+};
 
 pub mod actions;
 mod objects;
+pub mod random;
 mod systems;
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, States)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GamePhase {
     #[default]
     Inactive,
@@ -30,11 +38,25 @@ pub enum GamePhase {
 pub struct GameplayPlugin<Set: SystemSet, State: States> {
     set: Set,
     active_screen: State,
+    // the schedule that steps the match, if it isn't stepped every Update
+    schedule: Option<BoxedScheduleLabel>,
 }
 
 impl<Set: SystemSet, State: States> GameplayPlugin<Set, State> {
     pub fn new(set: Set, active_screen: State) -> Self {
-        GameplayPlugin { set, active_screen }
+        GameplayPlugin {
+            set,
+            active_screen,
+            schedule: None,
+        }
+    }
+
+    // Steps the match, physics included, only when `schedule` is run,
+    // so that the caller decides when and how often the match moves on.
+    // This must be the first GameplayPlugin added, since it decides where physics runs.
+    pub fn in_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.schedule = Some(Box::new(schedule));
+        self
     }
 }
 
@@ -57,21 +79,78 @@ impl<GameplaySet: SystemSet + Copy, State: States + Copy> Plugin
     for GameplayPlugin<GameplaySet, State>
 {
     fn build(&self, app: &mut App) {
+        let step_schedule = self.schedule.clone().unwrap_or_else(|| Box::new(Update));
         // if this has not been added yet, initialize physics, the marker, and GamePhase state
         if !GameplayMarkerPlugin::is_added(app) {
             println!("Add state gamephase");
             app.add_plugins(GameplayMarkerPlugin)
                 .add_state::<GamePhase>()
+                .add_state::<MatchPause>()
                 .insert_resource(RapierConfiguration {
                     gravity: Vec2::ZERO,
                     ..Default::default()
                 })
                 .init_resource::<Over>()
-                .add_plugins(RapierPhysicsPlugin::<()>::default());
+                .init_resource::<MatchRules>()
+                .init_resource::<random::MatchRng>()
+                .init_resource::<Conditions>()
+                .init_resource::<GustClock>()
+                .init_resource::<ShotClock>()
+                .init_resource::<BallCountdown>()
+                .init_resource::<PowerUpTimer>()
+                .init_resource::<PendingDelivery>()
+                .init_resource::<ExtraBallTimer>()
+                .init_resource::<Forfeit>()
+                .init_resource::<base::fielder::OutfieldRotation>()
+                .add_systems(OnEnter(MatchPause::Paused), systems::pause::pause_physics)
+                .add_systems(OnExit(MatchPause::Paused), systems::pause::resume_physics);
+            match &self.schedule {
+                None => {
+                    app.add_plugins(RapierPhysicsPlugin::<()>::default());
+                }
+                // physics moves first in each step, so that gameplay can respond to its events
+                // within the same step
+                Some(schedule) => {
+                    app.add_plugins(
+                        RapierPhysicsPlugin::<()>::default().with_default_system_setup(false),
+                    )
+                    .configure_sets(
+                        schedule.clone(),
+                        (
+                            PhysicsSet::SyncBackend,
+                            PhysicsSet::SyncBackendFlush,
+                            PhysicsSet::StepSimulation,
+                            PhysicsSet::Writeback,
+                        )
+                            .chain()
+                            .before(self.set),
+                    )
+                    .add_systems(
+                        schedule.clone(),
+                        (
+                            RapierPhysicsPlugin::<()>::get_systems(PhysicsSet::SyncBackend)
+                                .in_set(PhysicsSet::SyncBackend),
+                            RapierPhysicsPlugin::<()>::get_systems(PhysicsSet::SyncBackendFlush)
+                                .in_set(PhysicsSet::SyncBackendFlush),
+                            RapierPhysicsPlugin::<()>::get_systems(PhysicsSet::StepSimulation)
+                                .in_set(PhysicsSet::StepSimulation),
+                            RapierPhysicsPlugin::<()>::get_systems(PhysicsSet::Writeback)
+                                .in_set(PhysicsSet::Writeback),
+                        ),
+                    );
+                }
+            }
         }
 
         // in all cases, add all the gameplay systems to the defined SystemSet
-        app.add_systems(
+        // GamePhase transitions are shared between every GameplayPlugin, so the systems
+        // run on those transitions are also limited to this plugin's active screen
+        // nothing steps the match while it is paused, so its timers and actions wait too
+        app.configure_set(
+            step_schedule.clone(),
+            self.set.run_if(in_state(MatchPause::Running)),
+        )
+        .add_systems(
             OnEnter(self.active_screen),
             systems::scene::spawn_scene.in_set(self.set),
         )
@@ -84,16 +163,63 @@ impl<GameplaySet: SystemSet + Copy, State: States + Copy> Plugin
             )
                 .in_set(self.set),
         )
+        .add_systems(
+            OnExit(GamePhase::Active),
+            (systems::weather::still_ball, systems::tick::still_fielders)
+                .run_if(in_state(self.active_screen))
+                .in_set(self.set),
+        )
+        .add_systems(
+            OnEnter(GamePhase::Bowling),
+            systems::clock::start_shot_clock
+                .run_if(in_state(self.active_screen))
+                .in_set(self.set),
+        )
+        .add_systems(
+            OnExit(GamePhase::Bowling),
+            systems::clock::stop_shot_clock
+                .run_if(in_state(self.active_screen))
+                .in_set(self.set),
+        )
         .add_systems(
             OnEnter(GamePhase::Preparing),
-            systems::tick::ready_bowling_phase.in_set(self.set),
+            (
+                systems::tick::ready_bowling_phase,
+                systems::evolution::rest_fielders,
+                systems::powerplay::restrict_fielding,
+            )
+                .run_if(in_state(self.active_screen))
+                .in_set(self.set),
         )
         .add_systems(
-            Update,
+            step_schedule,
             (
+                systems::equipment::equip_bat.before(systems::tick::consume_actions),
+                systems::tick::count_down_ball.run_if(in_state(GamePhase::Preparing)),
+                systems::clock::run_shot_clock
+                    .before(systems::tick::consume_actions)
+                    .run_if(in_state(GamePhase::Bowling)),
                 systems::tick::consume_actions
                     .run_if(in_state(GamePhase::Bowling).or_else(in_state(GamePhase::Active))),
+                systems::tick::track_crease
+                    .after(systems::tick::consume_actions)
+                    .run_if(in_state(GamePhase::Bowling).or_else(in_state(GamePhase::Active))),
                 systems::scoring::register_goals.run_if(in_state(GamePhase::Active)),
+                systems::multi_ball::release_extra_balls.run_if(in_state(GamePhase::Active)),
+                systems::power_ups::spawn_power_ups.run_if(in_state(GamePhase::Active)),
+                systems::power_ups::collect_power_ups.run_if(in_state(GamePhase::Active)),
+                systems::power_ups::expire_power_ups
+                    .run_if(in_state(GamePhase::Bowling).or_else(in_state(GamePhase::Active))),
+                systems::power_ups::stretch_fielders,
+                systems::evolution::tire_fielders
+                    .after(systems::tick::consume_actions)
+                    .run_if(in_state(GamePhase::Bowling).or_else(in_state(GamePhase::Active))),
+                systems::tick::follow_rings
+                    .after(systems::evolution::tire_fielders)
+                    .run_if(in_state(GamePhase::Bowling).or_else(in_state(GamePhase::Active))),
+                systems::evolution::deflect_ball.run_if(in_state(GamePhase::Active)),
+                systems::weather::apply_conditions.run_if(in_state(GamePhase::Active)),
+                systems::surface::apply_surface.run_if(in_state(GamePhase::Active)),
             )
                 .in_set(self.set),
         );
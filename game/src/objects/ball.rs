@@ -1,35 +1,59 @@
 use bevy_ecs::prelude::Bundle;
+use bevy_math::Vec2;
+use bevy_render::prelude::SpatialBundle;
 use bevy_transform::prelude::Transform;
 
 use bevy_rapier2d::prelude::{
-    ActiveEvents, Collider, ColliderMassProperties, ExternalImpulse, RigidBody, Velocity,
+    ActiveEvents, Collider, ColliderMassProperties, Damping, ExternalForce, ExternalImpulse,
+    Restitution, RigidBody, Velocity,
 };
 
-use cricket_pong_base::ball::Ball;
+use cricket_pong_base::{
+    ball::{Ball, BallKind},
+    Position,
+};
 
 #[derive(Bundle)]
 pub struct BallBundle {
     ball: Ball,
     rigid_body: RigidBody,
-    transform: Transform,
+    spatial: SpatialBundle,
     velocity: Velocity,
     collider: Collider,
     mass: ColliderMassProperties,
+    restitution: Restitution,
     impulse: ExternalImpulse,
+    force: ExternalForce,
+    damping: Damping,
     events: ActiveEvents,
 }
 
 impl BallBundle {
-    pub fn new(transform: Transform) -> Self {
+    pub fn new(transform: Transform, kind: BallKind) -> Self {
         BallBundle {
-            ball: Ball,
+            ball: Ball::new(kind),
             rigid_body: RigidBody::Dynamic,
-            transform,
+            spatial: SpatialBundle::from_transform(transform),
             velocity: Velocity::default(),
-            collider: Collider::ball(Ball::RADIUS),
-            mass: ColliderMassProperties::Mass(5.),
+            collider: Collider::ball(kind.radius()),
+            mass: ColliderMassProperties::Mass(kind.mass()),
+            restitution: Restitution::coefficient(kind.restitution()),
             impulse: ExternalImpulse::default(),
+            force: ExternalForce::default(),
+            damping: Damping::default(),
             events: ActiveEvents::COLLISION_EVENTS,
         }
     }
+
+    // extra balls are bowled by the fielders, like any other
+    pub fn extra(mut self) -> Self {
+        self.ball.extra = true;
+        self.ball.last_touch = Some(Position::Fielder);
+        self
+    }
+
+    pub fn with_impulse(mut self, impulse: Vec2) -> Self {
+        self.impulse.impulse = impulse;
+        self
+    }
 }
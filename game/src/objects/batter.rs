@@ -7,11 +7,12 @@ use bevy_rapier2d::prelude::{
     Collider, ColliderMassProperties, MassProperties, RigidBody, Sensor, Velocity,
 };
 
-use cricket_pong_base::batter::{Batter, Wicket};
+use cricket_pong_base::batter::{Bat, Batter, Wicket};
 
 #[derive(Bundle)]
 struct BatterBundle {
     batter: Batter,
+    bat: Bat,
     rigid_body: RigidBody,
     spatial: SpatialBundle,
     velocity: Velocity,
@@ -21,23 +22,31 @@ struct BatterBundle {
 
 impl BatterBundle {
     pub fn new() -> Self {
-        let bat_true_radius = Batter::RADIUS + Batter::HWIDTH;
-        let inertia = Batter::MASS * bat_true_radius * bat_true_radius / 2.;
+        let bat = Bat::default();
+        let bat_true_radius = bat.crease_radius();
         BatterBundle {
             batter: Batter::default(),
+            bat,
             rigid_body: RigidBody::KinematicVelocityBased,
             spatial: SpatialBundle::from_transform(Transform::from_xyz(bat_true_radius, 0., 1.)),
             velocity: Velocity::zero(),
-            collider: Collider::cuboid(Batter::HWIDTH, Batter::HDEPTH),
-            mass: ColliderMassProperties::MassProperties(MassProperties {
-                local_center_of_mass: Vec2::new(-bat_true_radius, 0.),
-                mass: Batter::MASS,
-                principal_inertia: inertia,
-            }),
+            collider: Collider::cuboid(bat.hwidth, bat.hdepth),
+            mass: bat_mass_properties(bat_true_radius, bat.mass),
         }
     }
 }
 
+// the bat always rotates around the wicket, so its center of mass sits there
+// no matter how far the batter has stepped from it
+pub(crate) fn bat_mass_properties(bat_radius: f32, mass: f32) -> ColliderMassProperties {
+    let inertia = mass * bat_radius * bat_radius / 2.;
+    ColliderMassProperties::MassProperties(MassProperties {
+        local_center_of_mass: Vec2::new(-bat_radius, 0.),
+        mass,
+        principal_inertia: inertia,
+    })
+}
+
 #[derive(Bundle)]
 struct WicketBundle {
     wicket: Wicket,
@@ -49,7 +58,7 @@ struct WicketBundle {
 impl WicketBundle {
     pub fn new() -> Self {
         WicketBundle {
-            wicket: Wicket,
+            wicket: Wicket::default(),
             spatial: SpatialBundle::default(),
             collider: Collider::ball(Wicket::RADIUS),
             sensor: Sensor,
@@ -8,7 +8,10 @@ use bevy_rapier2d::prelude::{
     RigidBody, Sensor, Velocity,
 };
 
-use cricket_pong_base::fielder::{Boundary, Fielder, FielderRing};
+use cricket_pong_base::{
+    fielder::{Boundary, Fielder, FielderPosition, FielderRing},
+    GroundShape,
+};
 
 #[derive(Bundle)]
 struct FielderBundle {
@@ -22,12 +25,11 @@ struct FielderBundle {
 }
 
 impl FielderBundle {
-    fn new(fielder: Fielder) -> Self {
-        let radius = fielder.ring.radius();
-        let inertia = Fielder::MASS * radius * radius / 2.;
-        let translation = fielder.translation();
-        let rotation = fielder.rotation();
+    fn new(fielder: Fielder, ground: &GroundShape) -> Self {
+        let translation = fielder.translation(ground);
+        let rotation = fielder.rotation(ground);
         let hwidth = fielder.hwidth();
+        let inertia = Fielder::MASS * hwidth * hwidth / 3.;
         FielderBundle {
             fielder,
             rigid_body: RigidBody::KinematicVelocityBased,
@@ -36,8 +38,9 @@ impl FielderBundle {
                 Transform::from_translation(translation).with_rotation(rotation),
             ),
             collider: Collider::cuboid(hwidth, Fielder::HDEPTH),
+            // fielders are steered along their ring of the ground, rather than spun around the wicket
             mass: ColliderMassProperties::MassProperties(MassProperties {
-                local_center_of_mass: Vec2::new(0., -radius),
+                local_center_of_mass: Vec2::ZERO,
                 mass: Fielder::MASS,
                 principal_inertia: inertia,
             }),
@@ -47,22 +50,6 @@ impl FielderBundle {
             },
         }
     }
-
-    pub fn top(ring: FielderRing) -> Self {
-        FielderBundle::new(Fielder::top(ring))
-    }
-
-    pub fn bottom(ring: FielderRing) -> Self {
-        FielderBundle::new(Fielder::bottom(ring))
-    }
-
-    pub fn left(ring: FielderRing) -> Self {
-        FielderBundle::new(Fielder::left(ring))
-    }
-
-    pub fn right(ring: FielderRing) -> Self {
-        FielderBundle::new(Fielder::right(ring))
-    }
 }
 
 #[derive(Bundle)]
@@ -74,11 +61,16 @@ struct BoundaryBundle {
 }
 
 impl BoundaryBundle {
-    pub fn new() -> Self {
+    pub fn new(ground: &GroundShape) -> Self {
+        let collider = match ground {
+            GroundShape::Circle => Collider::ball(Boundary::RADIUS),
+            _ => Collider::convex_hull(&ground.outline(Boundary::RADIUS))
+                .unwrap_or_else(|| Collider::ball(Boundary::RADIUS)),
+        };
         BoundaryBundle {
             boundary: Boundary,
             spatial: SpatialBundle::default(),
-            collider: Collider::ball(Boundary::RADIUS),
+            collider,
             sensor: Sensor,
         }
     }
@@ -87,19 +79,30 @@ impl BoundaryBundle {
 pub struct FieldersSpawner;
 
 impl FieldersSpawner {
-    pub fn spawn(commands: &mut Commands) {
+    // only the first `outfielders` outfield positions are manned
+    pub fn spawn(commands: &mut Commands, ground: &GroundShape, outfielders: usize) {
         commands.spawn(FielderRing::Infield);
-        commands.spawn(FielderBundle::top(FielderRing::Infield));
-        commands.spawn(FielderBundle::bottom(FielderRing::Infield));
-        commands.spawn(FielderBundle::left(FielderRing::Infield));
-        commands.spawn(FielderBundle::right(FielderRing::Infield));
+        for position in FielderPosition::ALL {
+            Self::spawn_fielder(
+                commands,
+                Fielder::new(position, FielderRing::Infield),
+                ground,
+            );
+        }
 
         commands.spawn(FielderRing::Outfield);
-        commands.spawn(FielderBundle::top(FielderRing::Outfield));
-        commands.spawn(FielderBundle::bottom(FielderRing::Outfield));
-        commands.spawn(FielderBundle::left(FielderRing::Outfield));
-        commands.spawn(FielderBundle::right(FielderRing::Outfield));
+        for position in FielderPosition::ALL.into_iter().take(outfielders) {
+            Self::spawn_fielder(
+                commands,
+                Fielder::new(position, FielderRing::Outfield),
+                ground,
+            );
+        }
+
+        commands.spawn(BoundaryBundle::new(ground));
+    }
 
-        commands.spawn(BoundaryBundle::new());
+    pub fn spawn_fielder(commands: &mut Commands, fielder: Fielder, ground: &GroundShape) {
+        commands.spawn(FielderBundle::new(fielder, ground));
     }
 }
@@ -1,3 +1,5 @@
 pub mod ball;
 pub mod batter;
 pub mod field;
+pub mod obstacle;
+pub mod power_up;
@@ -0,0 +1,67 @@
+use bevy_ecs::prelude::{Bundle, Commands};
+use bevy_render::prelude::SpatialBundle;
+use bevy_transform::prelude::Transform;
+
+use bevy_rapier2d::prelude::{
+    CoefficientCombineRule, Collider, ColliderMassProperties, MassProperties, Restitution,
+    RigidBody, Sensor, Velocity,
+};
+
+use cricket_pong_base::{obstacle::Obstacle, FieldLayout, GroundShape};
+
+#[derive(Bundle)]
+struct ObstacleBundle {
+    obstacle: Obstacle,
+    rigid_body: RigidBody,
+    spatial: SpatialBundle,
+    velocity: Velocity,
+    collider: Collider,
+    mass: ColliderMassProperties,
+    restitution: Restitution,
+}
+
+impl ObstacleBundle {
+    fn new(obstacle: Obstacle, ground: &GroundShape) -> Self {
+        let translation = obstacle.translation(ground);
+        let (rigid_body, velocity) = match obstacle.orbit_speed {
+            Some(orbit_speed) => (
+                RigidBody::KinematicVelocityBased,
+                Velocity::angular(orbit_speed),
+            ),
+            None => (RigidBody::Fixed, Velocity::zero()),
+        };
+        ObstacleBundle {
+            rigid_body,
+            velocity,
+            spatial: SpatialBundle::from_transform(Transform::from_translation(
+                translation.extend(0.5),
+            )),
+            collider: Collider::ball(obstacle.radius),
+            // like the fielders, moving obstacles circle around the wicket
+            mass: ColliderMassProperties::MassProperties(MassProperties {
+                local_center_of_mass: -translation,
+                mass: Obstacle::MASS,
+                principal_inertia: Obstacle::MASS * translation.length_squared() / 2.,
+            }),
+            restitution: Restitution {
+                coefficient: obstacle.kind.restitution(),
+                combine_rule: CoefficientCombineRule::Max,
+            },
+            obstacle,
+        }
+    }
+}
+
+pub struct ObstaclesSpawner;
+
+impl ObstaclesSpawner {
+    pub fn spawn(commands: &mut Commands, layout: &FieldLayout, ground: &GroundShape) {
+        for obstacle in layout.obstacles.iter() {
+            let is_sensor = obstacle.kind.is_sensor();
+            let mut entity = commands.spawn(ObstacleBundle::new(obstacle.clone(), ground));
+            if is_sensor {
+                entity.insert(Sensor);
+            }
+        }
+    }
+}
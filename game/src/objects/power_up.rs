@@ -0,0 +1,29 @@
+use bevy_ecs::prelude::Bundle;
+use bevy_math::Vec2;
+use bevy_render::prelude::SpatialBundle;
+use bevy_transform::prelude::Transform;
+
+use bevy_rapier2d::prelude::{Collider, Sensor};
+
+use cricket_pong_base::power_up::{PowerUp, PowerUpKind};
+
+#[derive(Bundle)]
+pub struct PowerUpBundle {
+    power_up: PowerUp,
+    spatial: SpatialBundle,
+    collider: Collider,
+    sensor: Sensor,
+}
+
+impl PowerUpBundle {
+    pub fn new(kind: PowerUpKind, translation: Vec2, lifetime: f32) -> Self {
+        PowerUpBundle {
+            power_up: PowerUp::new(kind, lifetime),
+            spatial: SpatialBundle::from_transform(Transform::from_translation(
+                translation.extend(0.5),
+            )),
+            collider: Collider::ball(PowerUp::RADIUS),
+            sensor: Sensor,
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use bevy_ecs::prelude::Resource;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+// The only source of randomness in gameplay.
+// It is reseeded from the MatchRules at the start of every match, so that a match
+// played with the same seed and the same actions always plays out the same way.
+#[derive(Resource, Clone)]
+pub struct MatchRng(ChaCha8Rng);
+
+impl MatchRng {
+    pub fn new(seed: u64) -> Self {
+        MatchRng(ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    pub fn reseed(&mut self, seed: u64) {
+        *self = MatchRng::new(seed);
+    }
+
+    // a uniformly distributed index into a collection of `len` items
+    pub fn index(&mut self, len: usize) -> usize {
+        self.0.gen_range(0..len.max(1))
+    }
+
+    // a uniformly distributed value in [min, max)
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        if max <= min {
+            return min;
+        }
+        self.0.gen_range(min..max)
+    }
+
+    // a uniformly distributed value in [-magnitude, magnitude]
+    pub fn spread(&mut self, magnitude: f32) -> f32 {
+        if magnitude <= 0. {
+            return 0.;
+        }
+        self.0.gen_range(-magnitude..=magnitude)
+    }
+}
+
+impl Default for MatchRng {
+    fn default() -> Self {
+        MatchRng::new(0)
+    }
+}
@@ -0,0 +1,66 @@
+use bevy_ecs::prelude::{Query, Res, ResMut, With, Without};
+use bevy_time::prelude::Time;
+
+use cricket_pong_base::{
+    Identity, MatchRules, PlayerOne, PlayerTwo, Position, Score, ShotClock, ShotClockPenalty,
+};
+
+use crate::actions::{Action, Actions, FielderAction};
+
+// should be run OnEnter(GamePhase::Bowling)
+pub(crate) fn start_shot_clock(mut clock: ResMut<ShotClock>, rules: Res<MatchRules>) {
+    clock.remaining = rules
+        .shot_clock
+        .as_ref()
+        .map(|shot_clock| shot_clock.seconds);
+}
+
+// should be run OnExit(GamePhase::Bowling)
+pub(crate) fn stop_shot_clock(mut clock: ResMut<ShotClock>) {
+    clock.remaining = None;
+}
+
+// if the fielding side stalls for too long, bowl for them or give the batter a run
+#[allow(clippy::type_complexity)]
+pub(crate) fn run_shot_clock(
+    mut clock: ResMut<ShotClock>,
+    mut actions: ResMut<Actions>,
+    mut player_one_query: Query<(&mut Score, &Position), (With<PlayerOne>, Without<PlayerTwo>)>,
+    mut player_two_query: Query<(&mut Score, &Position), (With<PlayerTwo>, Without<PlayerOne>)>,
+    rules: Res<MatchRules>,
+    time: Res<Time>,
+) {
+    let Some(shot_clock) = &rules.shot_clock else { return };
+    let Some(remaining) = clock.remaining.as_mut() else { return };
+    *remaining -= time.delta_seconds();
+    if *remaining > 0. {
+        return;
+    }
+
+    match shot_clock.penalty {
+        ShotClockPenalty::AutoBowl => {
+            clock.remaining = None;
+            // the ball is bowled on behalf of whoever is fielding
+            let fielder = if player_two_query
+                .iter()
+                .any(|(_, position)| *position == Position::Fielder)
+            {
+                Identity::Two
+            } else {
+                Identity::One
+            };
+            actions.push(fielder, Action::Fielder(FielderAction::Bowl));
+        }
+        ShotClockPenalty::PenaltyRun => {
+            *remaining = shot_clock.seconds;
+            for (mut score, position) in player_one_query
+                .iter_mut()
+                .chain(player_two_query.iter_mut())
+            {
+                if *position == Position::Batter {
+                    score.0 += 1;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use bevy_ecs::prelude::{Query, With};
+use bevy_transform::prelude::Transform;
+
+use bevy_rapier2d::prelude::{Collider, ColliderMassProperties};
+
+use cricket_pong_base::{
+    batter::{Bat, BatKind, Batter},
+    power_up::{PowerUp, PowerUpEffect, PowerUpKind},
+    Position,
+};
+
+use crate::{objects::batter::bat_mass_properties, systems::power_ups::has_effect};
+
+// hands the bat picked by whoever is batting to the batter,
+// widened while the batter has a wide bat power-up
+pub(crate) fn equip_bat(
+    player_query: Query<(&Position, Option<&BatKind>)>,
+    effect_query: Query<&PowerUpEffect>,
+    mut batter_query: Query<
+        (
+            &mut Bat,
+            &mut Transform,
+            &mut Collider,
+            &mut ColliderMassProperties,
+        ),
+        With<Batter>,
+    >,
+) {
+    let Some(kind) = player_query.iter().find_map(|(position, kind)| {
+        if *position == Position::Batter {
+            Some(kind.copied().unwrap_or_default())
+        } else {
+            None
+        }
+    }) else {
+        return;
+    };
+    let mut bat = kind.bat();
+    if has_effect(&effect_query, PowerUpKind::WideBat, Position::Batter) {
+        bat.hwidth *= PowerUp::WIDE_BAT_SCALE;
+    }
+    for (mut equipped, mut transform, mut collider, mut mass) in batter_query.iter_mut() {
+        if *equipped == bat {
+            continue;
+        }
+        // keep the batter standing the same distance from the crease with the new bat
+        let offset = transform.translation.truncate();
+        let radius = offset.length() - equipped.crease_radius() + bat.crease_radius();
+        transform.translation =
+            (offset.normalize_or_zero() * radius).extend(transform.translation.z);
+        *collider = Collider::cuboid(bat.hwidth, bat.hdepth);
+        *mass = bat_mass_properties(radius, bat.mass);
+        *equipped = bat;
+    }
+}
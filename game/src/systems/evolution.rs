@@ -0,0 +1,64 @@
+use bevy_ecs::prelude::{EventReader, Query, Res, ResMut, With};
+use bevy_math::prelude::Vec2;
+use bevy_time::prelude::Time;
+
+use bevy_rapier2d::prelude::{CollisionEvent, Velocity};
+
+use cricket_pong_base::{ball::Ball, fielder::Fielder, MatchRules, Over};
+
+use crate::random::MatchRng;
+
+// fielders that keep moving get slower
+pub(crate) fn tire_fielders(
+    mut fielders_query: Query<(&mut Fielder, &Velocity)>,
+    rules: Res<MatchRules>,
+    time: Res<Time>,
+) {
+    let Some(fatigue) = &rules.fatigue else { return };
+    for (mut fielder, velocity) in fielders_query.iter_mut() {
+        if velocity.angvel != 0. {
+            fielder.fatigue =
+                (fielder.fatigue + fatigue.rate * time.delta_seconds()).min(fatigue.max_fatigue);
+        }
+    }
+}
+
+// fielders are fully rested at the start of each over
+// should be run OnEnter(GamePhase::Preparing)
+pub(crate) fn rest_fielders(mut fielders_query: Query<&mut Fielder>, over: Res<Over>) {
+    if !over.len().is_multiple_of(Over::LENGTH) {
+        return;
+    }
+    for mut fielder in fielders_query.iter_mut() {
+        fielder.fatigue = 0.;
+    }
+}
+
+// a worn pitch sends the ball off at unexpected angles when it bounces off a fielder
+pub(crate) fn deflect_ball(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut ball_query: Query<&mut Velocity, With<Ball>>,
+    fielder_query: Query<&Fielder>,
+    rules: Res<MatchRules>,
+    over: Res<Over>,
+    mut rng: ResMut<MatchRng>,
+) {
+    let Some(pitch) = &rules.pitch else { return };
+    let max_deflection = pitch.deflection_per_ball * pitch.wear(&over);
+    for event in collision_events.iter() {
+        let CollisionEvent::Started(entity1, entity2, _) = event else { continue };
+        let (ball, other_entity) = if ball_query.contains(*entity1) {
+            (*entity1, *entity2)
+        } else if ball_query.contains(*entity2) {
+            (*entity2, *entity1)
+        } else {
+            continue;
+        };
+        if !fielder_query.contains(other_entity) {
+            continue;
+        }
+        let Ok(mut velocity) = ball_query.get_mut(ball) else { continue };
+        let deflection = Vec2::from_angle(rng.spread(max_deflection));
+        velocity.linvel = deflection.rotate(velocity.linvel);
+    }
+}
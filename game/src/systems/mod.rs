@@ -1,3 +1,12 @@
+pub mod clock;
+pub mod equipment;
+pub mod evolution;
+pub mod multi_ball;
+pub mod pause;
+pub mod power_ups;
+pub mod powerplay;
 pub mod scene;
 pub mod scoring;
+pub mod surface;
 pub mod tick;
+pub mod weather;
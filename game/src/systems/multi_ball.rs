@@ -0,0 +1,50 @@
+use bevy_ecs::prelude::{Commands, DetectChanges, Query, Res, ResMut, State, With};
+use bevy_time::prelude::Time;
+use bevy_transform::prelude::{GlobalTransform, Transform};
+
+use cricket_pong_base::{ball::Ball, fielder::Fielder, ExtraBallTimer, MatchRules};
+
+use crate::{objects::ball::BallBundle, random::MatchRng, GamePhase};
+
+// in multi-ball matches, a random fielder bowls another ball every so often
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn release_extra_balls(
+    mut commands: Commands,
+    ball_query: Query<(), With<Ball>>,
+    fielder_query: Query<&GlobalTransform, With<Fielder>>,
+    state: Res<State<GamePhase>>,
+    rules: Res<MatchRules>,
+    mut rng: ResMut<MatchRng>,
+    time: Res<Time>,
+    mut timer: ResMut<ExtraBallTimer>,
+) {
+    let Some(multi_ball) = &rules.multi_ball else { return };
+    // start counting again with every delivery
+    if state.is_changed() {
+        timer.elapsed = 0.;
+    }
+    timer.elapsed += time.delta_seconds();
+    if timer.elapsed < multi_ball.interval {
+        return;
+    }
+    timer.elapsed = 0.;
+    if ball_query.iter().count() >= multi_ball.max_balls {
+        return;
+    }
+
+    let fielders: Vec<&GlobalTransform> = fielder_query.iter().collect();
+    if fielders.is_empty() {
+        return;
+    }
+    let origin = fielders[rng.index(fielders.len())].translation().truncate();
+    let direction = (-origin).normalize_or_zero();
+    let translation = origin + direction * (Fielder::HDEPTH + rules.ball.radius());
+    commands.spawn(
+        BallBundle::new(
+            Transform::from_translation(translation.extend(1.)),
+            rules.ball,
+        )
+        .extra()
+        .with_impulse(direction * Fielder::BOWL_IMPULSE),
+    );
+}
@@ -0,0 +1,15 @@
+use bevy_ecs::prelude::ResMut;
+
+use bevy_rapier2d::prelude::RapierConfiguration;
+
+// physics isn't part of the gameplay set, so has to be held still by itself
+
+// should be run OnEnter(MatchPause::Paused)
+pub(crate) fn pause_physics(mut config: ResMut<RapierConfiguration>) {
+    config.physics_pipeline_active = false;
+}
+
+// should be run OnExit(MatchPause::Paused)
+pub(crate) fn resume_physics(mut config: ResMut<RapierConfiguration>) {
+    config.physics_pipeline_active = true;
+}
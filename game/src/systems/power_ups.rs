@@ -0,0 +1,133 @@
+use bevy_ecs::prelude::{Commands, Entity, EventReader, Query, Res, ResMut, With};
+use bevy_time::prelude::Time;
+
+use bevy_rapier2d::prelude::{Collider, CollisionEvent};
+
+use cricket_pong_base::{
+    ball::Ball,
+    batter::Batter,
+    fielder::{Fielder, FielderRing},
+    power_up::{PowerUp, PowerUpEffect, PowerUpKind},
+    MatchRules, Position, PowerUpTimer,
+};
+
+use crate::{objects::power_up::PowerUpBundle, random::MatchRng};
+
+// whether `side` currently has an effect of this kind
+pub(crate) fn has_effect(
+    effect_query: &Query<&PowerUpEffect>,
+    kind: PowerUpKind,
+    side: Position,
+) -> bool {
+    effect_query
+        .iter()
+        .any(|effect| effect.kind == kind && effect.side == side)
+}
+
+// pickups appear at random spots between the crease and the outfield ring
+pub(crate) fn spawn_power_ups(
+    mut commands: Commands,
+    power_up_query: Query<(), With<PowerUp>>,
+    rules: Res<MatchRules>,
+    mut rng: ResMut<MatchRng>,
+    time: Res<Time>,
+    mut timer: ResMut<PowerUpTimer>,
+) {
+    let Some(power_ups) = &rules.power_ups else { return };
+    timer.elapsed += time.delta_seconds();
+    if timer.elapsed < power_ups.interval {
+        return;
+    }
+    timer.elapsed = 0.;
+    if power_up_query.iter().count() >= power_ups.max_pickups {
+        return;
+    }
+
+    let kind = PowerUpKind::ALL[rng.index(PowerUpKind::ALL.len())];
+    let angle = rng.spread(std::f32::consts::PI);
+    let radius = rng.range(
+        FielderRing::INFIELD_RADIUS / 2.,
+        FielderRing::OUTFIELD_RADIUS - PowerUp::RADIUS * 2.,
+    );
+    commands.spawn(PowerUpBundle::new(
+        kind,
+        rules.ground.point(radius, angle),
+        power_ups.lifetime,
+    ));
+}
+
+// remembers who last hit each ball, and hands them the pickups that ball passes through
+pub(crate) fn collect_power_ups(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut ball_query: Query<&mut Ball>,
+    batter_query: Query<(), With<Batter>>,
+    fielder_query: Query<(), With<Fielder>>,
+    power_up_query: Query<&PowerUp>,
+) {
+    let mut collected: Vec<Entity> = Vec::new();
+    for event in collision_events.iter() {
+        let CollisionEvent::Started(entity1, entity2, _flags) = event else { continue };
+        let (ball, other_entity) = if ball_query.contains(*entity1) {
+            (*entity1, *entity2)
+        } else if ball_query.contains(*entity2) {
+            (*entity2, *entity1)
+        } else {
+            continue;
+        };
+        let Ok(mut ball) = ball_query.get_mut(ball) else { continue };
+        if batter_query.contains(other_entity) {
+            ball.last_touch = Some(Position::Batter);
+        } else if fielder_query.contains(other_entity) {
+            ball.last_touch = Some(Position::Fielder);
+        } else if let Ok(power_up) = power_up_query.get(other_entity) {
+            let Some(side) = ball.last_touch else { continue };
+            if collected.contains(&other_entity) {
+                continue;
+            }
+            collected.push(other_entity);
+            commands.spawn(PowerUpEffect::new(power_up.kind, side));
+            commands.entity(other_entity).despawn();
+        }
+    }
+}
+
+// pickups disappear when they are left too long, and effects when they run out
+pub(crate) fn expire_power_ups(
+    mut commands: Commands,
+    mut power_up_query: Query<(Entity, &mut PowerUp)>,
+    mut effect_query: Query<(Entity, &mut PowerUpEffect)>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut power_up) in power_up_query.iter_mut() {
+        power_up.lifetime -= delta;
+        if power_up.lifetime <= 0. {
+            commands.entity(entity).despawn();
+        }
+    }
+    for (entity, mut effect) in effect_query.iter_mut() {
+        effect.remaining -= delta;
+        if effect.remaining <= 0. {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// fielders with a wide bat get wider paddles while it lasts
+pub(crate) fn stretch_fielders(
+    mut fielders_query: Query<(&mut Fielder, &mut Collider)>,
+    effect_query: Query<&PowerUpEffect>,
+) {
+    let reach = if has_effect(&effect_query, PowerUpKind::WideBat, Position::Fielder) {
+        PowerUp::WIDE_BAT_SCALE
+    } else {
+        1.
+    };
+    for (mut fielder, mut collider) in fielders_query.iter_mut() {
+        if fielder.reach != reach {
+            fielder.reach = reach;
+            *collider = Collider::cuboid(fielder.hwidth(), Fielder::HDEPTH);
+        }
+    }
+}
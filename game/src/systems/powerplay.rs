@@ -0,0 +1,41 @@
+use bevy_ecs::prelude::{Commands, Entity, Query, Res, ResMut};
+
+use cricket_pong_base::{
+    fielder::{Fielder, FielderPosition, FielderRing, OutfieldRotation},
+    MatchRules, Over,
+};
+
+use crate::objects::field::FieldersSpawner;
+
+// clears or mans outfield positions as each powerplay starts and ends
+// should be run OnEnter(GamePhase::Preparing)
+pub(crate) fn restrict_fielding(
+    mut commands: Commands,
+    fielders_query: Query<(Entity, &Fielder)>,
+    rules: Res<MatchRules>,
+    over: Res<Over>,
+    mut rotation: ResMut<OutfieldRotation>,
+) {
+    // the outfield turns together, so any outfielder shows where the rest belong
+    if let Some((_, fielder)) = fielders_query
+        .iter()
+        .find(|(_, fielder)| fielder.ring == FielderRing::Outfield)
+    {
+        rotation.0 = fielder.angle - fielder.position.angle();
+    }
+    let manned = &FielderPosition::ALL[..rules.outfielders(&over)];
+    for (entity, fielder) in fielders_query.iter() {
+        if fielder.ring == FielderRing::Outfield && !manned.contains(&fielder.position) {
+            commands.entity(entity).despawn();
+        }
+    }
+    for position in manned {
+        if !fielders_query.iter().any(|(_, fielder)| {
+            fielder.ring == FielderRing::Outfield && fielder.position == *position
+        }) {
+            let mut fielder = Fielder::new(*position, FielderRing::Outfield);
+            fielder.angle += rotation.0;
+            FieldersSpawner::spawn_fielder(&mut commands, fielder, &rules.ground);
+        }
+    }
+}
@@ -1,28 +1,47 @@
-use bevy_ecs::prelude::{Commands, Entity, NextState, Query, ResMut, With};
+use bevy_ecs::prelude::{Commands, Entity, NextState, Query, Res, ResMut, With};
+use bevy_hierarchy::DespawnRecursiveExt;
 use bevy_transform::prelude::Transform;
 
 use cricket_pong_base::{
     ball::Ball,
     batter::{Batter, Wicket},
-    fielder::{Boundary, Fielder, FielderRing},
-    Over, PlayerOne, PlayerTwo,
+    fielder::{Boundary, Fielder, FielderRing, OutfieldRotation},
+    obstacle::Obstacle,
+    power_up::{PowerUp, PowerUpEffect},
+    BallCountdown, Conditions, ExtraBallTimer, Forfeit, GustClock, MatchPause, MatchRules, Over,
+    PendingDelivery, PlayerOne, PlayerTwo, PowerUpTimer, ShotClock, WindVane,
 };
 
 use crate::{
     actions::Actions,
-    objects::{ball::BallBundle, batter::BatterSpawner, field::FieldersSpawner},
+    objects::{
+        ball::BallBundle, batter::BatterSpawner, field::FieldersSpawner, obstacle::ObstaclesSpawner,
+    },
+    random::MatchRng,
+    systems::weather::roll_conditions,
     GamePhase,
 };
 
 // should be run OnEnter(MyGameState)
-pub(crate) fn spawn_scene(mut commands: Commands, mut state: ResMut<NextState<GamePhase>>) {
-    commands.spawn(BallBundle::new(Transform::from_xyz(0., 0., 1.)));
-    FieldersSpawner::spawn(&mut commands);
+pub(crate) fn spawn_scene(
+    mut commands: Commands,
+    mut state: ResMut<NextState<GamePhase>>,
+    rules: Res<MatchRules>,
+    over: Res<Over>,
+    mut rng: ResMut<MatchRng>,
+) {
+    rng.reseed(rules.seed);
+    commands.insert_resource(roll_conditions(rules.weather, &mut rng));
+    commands.spawn(WindVane);
+    commands.spawn(BallBundle::new(Transform::from_xyz(0., 0., 1.), rules.ball));
+    FieldersSpawner::spawn(&mut commands, &rules.ground, rules.outfielders(&over));
+    ObstaclesSpawner::spawn(&mut commands, &rules.layout, &rules.ground);
     BatterSpawner::spawn(&mut commands);
     state.set(GamePhase::Preparing);
 }
 
 // should be run OnExit(MyGameState)
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn despawn_scene(
     mut commands: Commands,
     boundary_query: Query<Entity, With<Boundary>>,
@@ -33,6 +52,10 @@ pub(crate) fn despawn_scene(
     ball_query: Query<Entity, With<Ball>>,
     player_one_query: Query<Entity, With<PlayerOne>>,
     player_two_query: Query<Entity, With<PlayerTwo>>,
+    wind_vane_query: Query<Entity, With<WindVane>>,
+    obstacle_query: Query<Entity, With<Obstacle>>,
+    power_up_query: Query<Entity, With<PowerUp>>,
+    effect_query: Query<Entity, With<PowerUpEffect>>,
 ) {
     for entity in boundary_query.iter() {
         commands.entity(entity).despawn();
@@ -47,7 +70,7 @@ pub(crate) fn despawn_scene(
         commands.entity(entity).despawn();
     }
     for entity in batter_query.iter() {
-        commands.entity(entity).despawn();
+        commands.entity(entity).despawn_recursive();
     }
     for entity in ball_query.iter() {
         commands.entity(entity).despawn();
@@ -58,14 +81,53 @@ pub(crate) fn despawn_scene(
     for entity in player_two_query.iter() {
         commands.entity(entity).despawn();
     }
+    for entity in wind_vane_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    for entity in obstacle_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    for entity in power_up_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    for entity in effect_query.iter() {
+        commands.entity(entity).despawn();
+    }
 }
 
 // should be run OnExit(MyGameState)
-pub(crate) fn deactivate_game_phase(mut state: ResMut<NextState<GamePhase>>) {
+// a match left while paused isn't still paused when the next one starts
+pub(crate) fn deactivate_game_phase(
+    mut state: ResMut<NextState<GamePhase>>,
+    mut pause: ResMut<NextState<MatchPause>>,
+) {
     state.set(GamePhase::Inactive);
+    pause.set(MatchPause::Running);
 }
 
-pub(crate) fn cleanup_resources(mut overs: ResMut<Over>, mut actions: ResMut<Actions>) {
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn cleanup_resources(
+    mut overs: ResMut<Over>,
+    mut actions: ResMut<Actions>,
+    mut conditions: ResMut<Conditions>,
+    mut gust_clock: ResMut<GustClock>,
+    mut shot_clock: ResMut<ShotClock>,
+    mut countdown: ResMut<BallCountdown>,
+    mut power_up_timer: ResMut<PowerUpTimer>,
+    mut delivery: ResMut<PendingDelivery>,
+    mut extra_ball_timer: ResMut<ExtraBallTimer>,
+    mut forfeit: ResMut<Forfeit>,
+    mut rotation: ResMut<OutfieldRotation>,
+) {
     overs.clear();
     actions.0.clear();
+    *conditions = Conditions::default();
+    *gust_clock = GustClock::default();
+    *shot_clock = ShotClock::default();
+    *countdown = BallCountdown::default();
+    *power_up_timer = PowerUpTimer::default();
+    *delivery = PendingDelivery::default();
+    *extra_ball_timer = ExtraBallTimer::default();
+    *forfeit = Forfeit::default();
+    *rotation = OutfieldRotation::default();
 }
@@ -1,4 +1,4 @@
-use bevy_ecs::prelude::{EventReader, Local, NextState, Query, ResMut, With, Without};
+use bevy_ecs::prelude::{Commands, Entity, EventReader, NextState, Query, ResMut, With, Without};
 
 use bevy_rapier2d::{prelude::CollisionEvent, rapier::prelude::CollisionEventFlags};
 
@@ -6,90 +6,143 @@ use cricket_pong_base::{
     ball::Ball,
     batter::Wicket,
     fielder::{Boundary, Fielder},
-    BowlResult, BowlScore, Identity, Over, PlayerOne, PlayerTwo, Position, Score,
+    obstacle::Obstacle,
+    power_up::{PowerUpEffect, PowerUpKind},
+    BowlResult, BowlScore, Identity, Over, PendingDelivery, PlayerOne, PlayerTwo, Position, Score,
 };
 
-use crate::GamePhase;
+use crate::{systems::power_ups::has_effect, GamePhase};
 
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub(crate) fn register_goals(
+    mut commands: Commands,
     mut collision_events: EventReader<CollisionEvent>,
     mut player_one_query: Query<(&mut Score, &mut Position), (With<PlayerOne>, Without<PlayerTwo>)>,
     mut player_two_query: Query<(&mut Score, &mut Position), (With<PlayerTwo>, Without<PlayerOne>)>,
-    ball_query: Query<&Ball>,
+    mut ball_query: Query<(Entity, &mut Ball)>,
     wicket_query: Query<&Wicket>,
     boundary_query: Query<&Boundary>,
     fielder_query: Query<&Fielder>,
+    obstacle_query: Query<&Obstacle>,
     mut over: ResMut<Over>,
     mut state: ResMut<NextState<GamePhase>>,
-    mut pass_count: Local<u8>,
+    effect_query: Query<&PowerUpEffect>,
+    mut delivery_score: ResMut<PendingDelivery>,
 ) {
-    let mut score_points = move |scored_points: u16, scoring_position: Position| {
-        let (player_one_score, mut player_one_position) = player_one_query.single_mut();
-        let (player_two_score, mut player_two_position) = player_two_query.single_mut();
-        let (mut score, scorer) = if *player_one_position == scoring_position {
-            (player_one_score, Identity::One)
-        } else if *player_two_position == scoring_position {
-            (player_two_score, Identity::Two)
-        } else {
-            return;
-        };
-        score.0 += scored_points;
-
-        let bowl_result = over.push(BowlScore {
-            scorer,
-            value: scored_points,
-        });
-        state.set(GamePhase::Preparing);
-        match bowl_result {
-            BowlResult::None => {}
-            BowlResult::ChangePositions => {
-                *player_one_position = !*player_one_position;
-                *player_two_position = !*player_two_position;
-            }
-            BowlResult::GameOver => {
-                state.set(GamePhase::GameOver);
-            }
-        }
-    };
-
+    let mut scored_balls: Vec<(Entity, u16, Position)> = Vec::new();
+    let mut bonus_runs: u16 = 0;
     for event in collision_events.iter() {
         // score 1 for batter if the ball goes outside the boundary
         if let CollisionEvent::Stopped(entity1, entity2, flags) = event {
             if flags.contains(CollisionEventFlags::REMOVED) {
                 continue;
             };
-            let other_entity = if ball_query.contains(*entity1) {
-                *entity2
+            let (ball, other_entity) = if ball_query.contains(*entity1) {
+                (*entity1, *entity2)
             } else if ball_query.contains(*entity2) {
-                *entity1
+                (*entity2, *entity1)
             } else {
                 continue;
             };
             if boundary_query.contains(other_entity) {
-                score_points(1, Position::Batter);
-                *pass_count = 0;
+                scored_balls.push((ball, 1, Position::Batter));
             }
         }
         if let CollisionEvent::Started(entity1, entity2, _flags) = event {
-            let other_entity = if ball_query.contains(*entity1) {
-                *entity2
+            let (ball, other_entity) = if ball_query.contains(*entity1) {
+                (*entity1, *entity2)
             } else if ball_query.contains(*entity2) {
-                *entity1
+                (*entity2, *entity1)
             } else {
                 continue;
             };
             if wicket_query.contains(other_entity) {
                 // score 3 for fielder if the ball hits the wicket
-                score_points(3, Position::Fielder);
-                *pass_count = 0;
+                // (while the batter is out of the crease, this is a stumping)
+                scored_balls.push((ball, 3, Position::Fielder));
             } else if fielder_query.contains(other_entity) {
                 // score 1 for fielder if the ball is passed between paddles 5 times
-                *pass_count += 1;
-                if *pass_count >= 5 {
-                    score_points(1, Position::Fielder);
-                    *pass_count = 0;
+                let Ok((_, mut ball_state)) = ball_query.get_mut(ball) else { continue };
+                ball_state.passes += 1;
+                if ball_state.passes >= 5 {
+                    scored_balls.push((ball, 1, Position::Fielder));
                 }
+            } else if let Ok(obstacle) = obstacle_query.get(other_entity) {
+                // bonus obstacles pay the batter without ending the delivery
+                bonus_runs += obstacle.bonus_runs.unwrap_or_default();
             }
         }
     }
+    if scored_balls.is_empty() && bonus_runs == 0 {
+        return;
+    }
+
+    // a double runs power-up doubles everything its side scores
+    let multiplier = |position: Position| {
+        if has_effect(&effect_query, PowerUpKind::DoubleRuns, position) {
+            2
+        } else {
+            1
+        }
+    };
+    let (mut player_one_score, mut player_one_position) = player_one_query.single_mut();
+    let (mut player_two_score, mut player_two_position) = player_two_query.single_mut();
+    let bonus_runs = bonus_runs * multiplier(Position::Batter);
+    if bonus_runs > 0 {
+        if *player_one_position == Position::Batter {
+            player_one_score.0 += bonus_runs;
+        } else if *player_two_position == Position::Batter {
+            player_two_score.0 += bonus_runs;
+        }
+    }
+    let mut balls_in_play = ball_query.iter().count();
+    let mut resolved_balls: Vec<Entity> = Vec::new();
+    for (ball, scored_points, scoring_position) in scored_balls {
+        let scored_points = scored_points * multiplier(scoring_position);
+        // each ball can only be scored once
+        if resolved_balls.contains(&ball) {
+            continue;
+        }
+        let Ok((_, ball_state)) = ball_query.get(ball) else { continue };
+        resolved_balls.push(ball);
+
+        let (score, scorer) = if *player_one_position == scoring_position {
+            (&mut player_one_score, Identity::One)
+        } else if *player_two_position == scoring_position {
+            (&mut player_two_score, Identity::Two)
+        } else {
+            continue;
+        };
+        score.0 += scored_points;
+        // extra balls only award bonus points, the over tracks the delivery itself
+        if !ball_state.extra {
+            delivery_score.0 = Some(BowlScore {
+                scorer,
+                value: scored_points,
+            });
+        }
+
+        balls_in_play -= 1;
+        // the last ball is kept around to be bowled next
+        if balls_in_play > 0 {
+            commands.entity(ball).despawn();
+        }
+    }
+    // the delivery is only over once every ball has been scored
+    if balls_in_play > 0 {
+        return;
+    }
+
+    state.set(GamePhase::Preparing);
+    let Some(score) = delivery_score.0.take() else { return };
+    match over.push(score) {
+        BowlResult::None => {}
+        BowlResult::ChangePositions => {
+            *player_one_position = !*player_one_position;
+            *player_two_position = !*player_two_position;
+        }
+        BowlResult::GameOver => {
+            state.set(GamePhase::GameOver);
+        }
+    }
 }
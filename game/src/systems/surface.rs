@@ -0,0 +1,58 @@
+use bevy_ecs::prelude::{Query, Res};
+use bevy_transform::prelude::GlobalTransform;
+
+use bevy_rapier2d::prelude::Damping;
+
+use cricket_pong_base::{
+    ball::Ball,
+    obstacle::{Obstacle, ObstacleKind},
+    power_up::{PowerUp, PowerUpEffect, PowerUpKind},
+    MatchRules, Over,
+};
+
+// slow the ball down according to the part of the surface it is rolling over,
+// plus any drag from a worn pitch, rough patch or slow ball power-up,
+// scaled by how draggy the ball itself is
+pub(crate) fn apply_surface(
+    mut ball_query: Query<(&Ball, &GlobalTransform, &mut Damping)>,
+    obstacle_query: Query<(&Obstacle, &GlobalTransform)>,
+    effect_query: Query<&PowerUpEffect>,
+    rules: Res<MatchRules>,
+    over: Res<Over>,
+) {
+    let wear_drag = rules
+        .pitch
+        .as_ref()
+        .map_or(0., |pitch| pitch.drag_per_ball * pitch.wear(&over));
+    let slow_ball_drag = if effect_query
+        .iter()
+        .any(|effect| effect.kind == PowerUpKind::SlowBall)
+    {
+        PowerUp::SLOW_BALL_DAMPING
+    } else {
+        0.
+    };
+    for (ball, transform, mut damping) in ball_query.iter_mut() {
+        let position = transform.translation().truncate();
+        let zone = rules.surface.zone_at(rules.ground.nominal_radius(position));
+        let rough_drag = obstacle_query
+            .iter()
+            .filter(|(obstacle, obstacle_transform)| {
+                obstacle.kind == ObstacleKind::SlowZone
+                    && obstacle_transform
+                        .translation()
+                        .truncate()
+                        .distance(position)
+                        < obstacle.radius
+            })
+            .map(|_| Obstacle::SLOW_ZONE_DAMPING)
+            .sum::<f32>();
+        let drag = ball.kind.drag();
+        let linear_damping = (zone.linear_damping + wear_drag + rough_drag + slow_ball_drag) * drag;
+        let angular_damping = zone.angular_damping * drag;
+        if damping.linear_damping != linear_damping || damping.angular_damping != angular_damping {
+            damping.linear_damping = linear_damping;
+            damping.angular_damping = angular_damping;
+        }
+    }
+}
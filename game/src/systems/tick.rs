@@ -1,29 +1,50 @@
-use bevy_ecs::prelude::{Commands, Entity, NextState, Query, Res, ResMut, State, With, Without};
+use bevy_ecs::{
+    prelude::{Commands, Entity, NextState, Query, Res, ResMut, State, With, Without},
+    query::Has,
+};
 use bevy_hierarchy::prelude::BuildChildren;
-use bevy_math::prelude::Vec2;
+use bevy_math::prelude::{Vec2, Vec3};
 use bevy_time::prelude::Time;
 use bevy_transform::prelude::{GlobalTransform, Transform};
 
-use bevy_rapier2d::prelude::{ExternalImpulse, Velocity};
+use bevy_rapier2d::prelude::{Collider, ColliderMassProperties, ExternalImpulse, Velocity};
 
 use cricket_pong_base::{
     ball::Ball,
-    batter::Batter,
+    batter::{Bat, Batter, Wicket},
     fielder::{Fielder, FielderPosition, FielderRing},
+    power_up::{PowerUpEffect, PowerUpKind},
+    BallCountdown, Identity, PlayerOne, Position, Score,
 };
 
 use crate::{
-    actions::{Action, Actions, BatterAction, FielderAction},
-    GamePhase,
+    actions::{Action, Actions, BatterAction, FielderAction, QueuedAction},
+    objects::batter::bat_mass_properties,
+    systems::power_ups::has_effect,
+    GamePhase, MatchRules, Over,
 };
 
 pub(crate) fn ready_bowling_phase(
     mut commands: Commands,
-    mut ball_query: Query<(Entity, &mut Transform, &mut Velocity), With<Ball>>,
+    mut ball_query: Query<(Entity, &mut Ball, &mut Transform, &mut Velocity)>,
     fielders_query: Query<(Entity, &Fielder)>,
+    mut batter_query: Query<&mut Batter>,
     mut state: ResMut<NextState<GamePhase>>,
+    mut countdown: ResMut<BallCountdown>,
+    rules: Res<MatchRules>,
 ) {
-    let Ok((ball, mut transform, mut velocity)) = ball_query.get_single_mut() else { return };
+    for mut batter in batter_query.iter_mut() {
+        batter.recover_stamina();
+    }
+    let mut balls = ball_query.iter_mut();
+    let Some((ball, mut ball_state, mut transform, mut velocity)) = balls.next() else { return };
+    // only one ball is bowled, any others are left over from multi-ball play
+    for (extra_ball, _, _, _) in balls {
+        commands.entity(extra_ball).despawn();
+    }
+    ball_state.passes = 0;
+    ball_state.extra = false;
+    ball_state.last_touch = Some(Position::Fielder);
     if let Some(fielder) = fielders_query.iter().find_map(|(entity, fielder)| {
         if fielder.position == FielderPosition::Top && fielder.ring == FielderRing::Infield {
             Some(entity)
@@ -33,19 +54,73 @@ pub(crate) fn ready_bowling_phase(
     }) {
         commands.entity(fielder).add_child(ball);
         transform.translation.x = 0.;
-        transform.translation.y = -(Fielder::HDEPTH + Ball::RADIUS);
+        transform.translation.y = -(Fielder::HDEPTH + ball_state.kind.radius());
         *velocity = Velocity::zero();
-        state.set(GamePhase::Bowling);
+        if rules.pause.is_none() && !rules.ready_check {
+            state.set(GamePhase::Bowling);
+        } else {
+            *countdown = BallCountdown {
+                remaining: Some(rules.pause.unwrap_or_default()),
+                ..Default::default()
+            };
+        }
+    }
+}
+
+// players can only act for the position they are currently playing
+fn is_authorized(
+    queued: &QueuedAction,
+    player_query: &Query<(&Position, Has<PlayerOne>), With<Score>>,
+) -> bool {
+    player_query.iter().any(|(position, is_player_one)| {
+        Identity::of(is_player_one) == queued.player && *position == queued.action.position()
+    })
+}
+
+// waits out the pause between balls, and for both sides to be ready if the rules ask for it
+pub(crate) fn count_down_ball(
+    mut actions: ResMut<Actions>,
+    mut countdown: ResMut<BallCountdown>,
+    mut state: ResMut<NextState<GamePhase>>,
+    player_query: Query<(&Position, Has<PlayerOne>), With<Score>>,
+    rules: Res<MatchRules>,
+    time: Res<Time>,
+) {
+    // nothing else can be done until the ball is ready to be bowled
+    for QueuedAction { action, .. } in actions
+        .0
+        .drain(..)
+        .filter(|queued| is_authorized(queued, &player_query))
+    {
+        match action {
+            Action::Batter(BatterAction::Ready) => countdown.batter_ready = true,
+            Action::Fielder(FielderAction::Ready) => countdown.fielder_ready = true,
+            _ => {}
+        }
+    }
+    let Some(remaining) = countdown.remaining.as_mut() else { return };
+    *remaining = (*remaining - time.delta_seconds()).max(0.);
+    if *remaining > 0. {
+        return;
+    }
+    if rules.ready_check && !(countdown.batter_ready && countdown.fielder_ready) {
+        return;
     }
+    *countdown = BallCountdown::default();
+    state.set(GamePhase::Bowling);
 }
 
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub(crate) fn consume_actions(
     mut commands: Commands,
     mut actions: ResMut<Actions>,
     state: Res<State<GamePhase>>,
     mut next_state: ResMut<NextState<GamePhase>>,
     mut fielders_query: Query<(&Fielder, &mut Velocity)>,
-    mut batter_query: Query<(&mut Batter, &mut Velocity), Without<Fielder>>,
+    mut batter_query: Query<
+        (&mut Batter, &Bat, &Transform, &mut Velocity),
+        (Without<Fielder>, Without<Ball>),
+    >,
     mut ball_query: Query<
         (
             Entity,
@@ -55,15 +130,32 @@ pub(crate) fn consume_actions(
         ),
         With<Ball>,
     >,
+    effect_query: Query<&PowerUpEffect>,
+    player_query: Query<(&Position, Has<PlayerOne>), With<Score>>,
+    rules: Res<MatchRules>,
+    over: Res<Over>,
     time: Res<Time>,
 ) {
+    // a frozen ring power-up stops the other side from moving around the field
+    let batter_frozen = has_effect(&effect_query, PowerUpKind::FrozenRing, Position::Fielder);
+    let infield_frozen = has_effect(&effect_query, PowerUpKind::FrozenRing, Position::Batter);
+    let outfield_locked = rules.locks_outfield(&over);
     for (_, mut velocity) in fielders_query.iter_mut() {
         *velocity = Velocity::zero();
     }
-    if let Ok((mut bat, mut velocity)) = batter_query.get_single_mut() {
+    if let Ok((mut bat, _, _, mut velocity)) = batter_query.get_single_mut() {
+        velocity.linvel = Vec2::ZERO;
+        if let Some(recovery_timer) = bat.recovery_timer.as_mut() {
+            if *recovery_timer <= 0. {
+                bat.recovery_timer = None;
+            } else {
+                *recovery_timer -= time.delta_seconds();
+            }
+        }
         if let Some(swing_timer) = bat.swing_timer.as_mut() {
             if *swing_timer <= 0. {
                 bat.swing_timer = None;
+                bat.recovery_timer = rules.swing_recovery_time;
                 velocity.angvel = 0.;
             } else {
                 *swing_timer -= time.delta_seconds();
@@ -72,7 +164,11 @@ pub(crate) fn consume_actions(
             velocity.angvel = 0.;
         }
     }
-    for action in actions.0.drain(..) {
+    for QueuedAction { action, .. } in actions
+        .0
+        .drain(..)
+        .filter(|queued| is_authorized(queued, &player_query))
+    {
         match action {
             Action::Fielder(FielderAction::Bowl) => {
                 if *state != GamePhase::Bowling || next_state.0.is_some() {
@@ -100,35 +196,169 @@ pub(crate) fn consume_actions(
                     }
                     _ => continue,
                 };
+                if (infield_frozen && ring_to_match == FielderRing::Infield)
+                    || (outfield_locked && ring_to_match == FielderRing::Outfield)
+                {
+                    continue;
+                }
                 let Some(rotation_direction) = movement.rotation_direction() else { continue };
                 for (fielder, mut velocity) in fielders_query.iter_mut() {
                     if fielder.ring == ring_to_match {
-                        velocity.angvel = rotation_direction * Fielder::ROTATION_SPEED;
+                        velocity.angvel = rotation_direction * fielder.rotation_speed();
                     }
                 }
             }
             Action::Batter(movement) => {
-                if let Ok((mut bat, mut velocity)) = batter_query.get_single_mut() {
+                if let Ok((mut bat, equipment, transform, mut velocity)) =
+                    batter_query.get_single_mut()
+                {
                     if bat.swing_timer.is_none() {
                         let angular_velocity = movement.rotation_direction()
                             * match movement {
                                 BatterAction::MoveCW | BatterAction::MoveCCW => {
+                                    if batter_frozen {
+                                        continue;
+                                    }
                                     Batter::ROTATION_SPEED
                                 }
                                 BatterAction::SwingCW | BatterAction::SwingCCW => {
-                                    Batter::SWING_VELOCITY
+                                    let charge = bat.charge.take().unwrap_or_default();
+                                    if bat.recovery_timer.is_some() {
+                                        continue;
+                                    }
+                                    let power = Batter::swing_power(charge) * bat.stamina_power();
+                                    bat.spend_stamina();
+                                    equipment.swing_velocity * power
+                                }
+                                BatterAction::ChargeSwing => {
+                                    let charge = bat.charge.get_or_insert(0.);
+                                    *charge += time.delta_seconds();
+                                    continue;
                                 }
+                                BatterAction::StepIn | BatterAction::StepOut => {
+                                    let step_direction = movement.step_direction();
+                                    let offset = transform.translation.truncate();
+                                    let radius = offset.length();
+                                    if (step_direction > 0. && radius < equipment.max_step_radius())
+                                        || (step_direction < 0.
+                                            && radius > equipment.min_step_radius())
+                                    {
+                                        velocity.linvel = offset.normalize_or_zero()
+                                            * step_direction
+                                            * Batter::STEP_SPEED;
+                                    }
+                                    continue;
+                                }
+                                BatterAction::Ready => continue,
                             };
                         match movement {
                             BatterAction::SwingCW | BatterAction::SwingCCW => {
-                                bat.swing_timer = Some(Batter::SWING_TIME);
+                                bat.swing_timer = Some(equipment.swing_time);
                             }
                             _ => {}
                         };
                         velocity.angvel = angular_velocity;
+                    } else if matches!(movement, BatterAction::SwingCW | BatterAction::SwingCCW) {
+                        // a release while the bat is still swinging lets go of the wind-up
+                        bat.charge = None;
                     }
                 }
             }
         }
     }
 }
+
+// keeps the bat rotating around the wicket as it steps in and out of the crease,
+// and exposes the wicket when the batter has wandered too far from it
+pub(crate) fn track_crease(
+    mut batter_query: Query<
+        (&Bat, &Transform, &mut Velocity, &mut ColliderMassProperties),
+        With<Batter>,
+    >,
+    mut wicket_query: Query<(&mut Wicket, &mut Collider)>,
+) {
+    let Ok((bat, transform, mut velocity, mut mass)) = batter_query.get_single_mut() else { return };
+    let offset = transform.translation.truncate();
+    let radius = offset.length();
+
+    let outward_speed = velocity.linvel.dot(offset);
+    if (radius >= bat.max_step_radius() && outward_speed > 0.)
+        || (radius <= bat.min_step_radius() && outward_speed < 0.)
+    {
+        velocity.linvel = Vec2::ZERO;
+    }
+
+    if let ColliderMassProperties::MassProperties(properties) = &*mass {
+        if (properties.local_center_of_mass.x + radius).abs() > f32::EPSILON
+            || properties.mass != bat.mass
+        {
+            *mass = bat_mass_properties(radius, bat.mass);
+        }
+    }
+
+    let exposed = radius > bat.exposed_step_radius();
+    for (mut wicket, mut collider) in wicket_query.iter_mut() {
+        if wicket.exposed != exposed {
+            wicket.exposed = exposed;
+            *collider = Collider::ball(wicket.radius());
+        }
+    }
+}
+
+// fielders travel along their ring of the ground: the rate they were asked to move around
+// the wicket becomes the velocity that carries them to their next spot on the curve
+pub(crate) fn follow_rings(
+    mut fielders_query: Query<(&mut Fielder, &Transform, &mut Velocity)>,
+    rules: Res<MatchRules>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_seconds();
+    if delta <= 0. {
+        return;
+    }
+    for (mut fielder, transform, mut velocity) in fielders_query.iter_mut() {
+        let radius = fielder.ring.radius();
+        let angle = (fielder.angle + velocity.angvel * delta).rem_euclid(std::f32::consts::TAU);
+        let target = rules.ground.point(radius, angle);
+        let facing = rules.ground.normal(radius, angle);
+        let current_facing = (transform.rotation * Vec3::Y).truncate();
+        velocity.linvel = (target - transform.translation.truncate()) / delta;
+        velocity.angvel = current_facing.angle_between(facing) / delta;
+        fielder.angle = angle;
+    }
+}
+
+// should be run OnExit(GamePhase::Active)
+pub(crate) fn still_fielders(mut fielders_query: Query<&mut Velocity, With<Fielder>>) {
+    for mut velocity in fielders_query.iter_mut() {
+        *velocity = Velocity::zero();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::{prelude::World, system::SystemState};
+
+    use cricket_pong_base::PlayerTwo;
+
+    use super::*;
+
+    #[test]
+    fn players_are_told_apart_by_their_markers() {
+        let mut world = World::new();
+        // player two is batting, so it's the markers that decide who plays where
+        world.spawn((Position::Fielder, PlayerOne, Score(0)));
+        world.spawn((Position::Batter, PlayerTwo, Score(0)));
+        let mut players =
+            SystemState::<Query<(&Position, Has<PlayerOne>), With<Score>>>::new(&mut world);
+        let players = players.get(&world);
+
+        let swing = Action::Batter(BatterAction::SwingCW);
+        let bowl = Action::Fielder(FielderAction::Bowl);
+        let authorized = |player, action| is_authorized(&QueuedAction { player, action }, &players);
+        assert!(authorized(Identity::Two, swing));
+        assert!(!authorized(Identity::One, swing));
+        assert!(authorized(Identity::One, bowl));
+        assert!(!authorized(Identity::Two, bowl));
+    }
+}
@@ -0,0 +1,44 @@
+use bevy_ecs::prelude::{Query, Res, ResMut, With};
+use bevy_math::prelude::Vec2;
+use bevy_time::prelude::Time;
+
+use bevy_rapier2d::prelude::{ExternalForce, Velocity};
+
+use cricket_pong_base::{ball::Ball, Conditions, GustClock, Weather};
+
+use crate::random::MatchRng;
+
+pub(crate) fn roll_conditions(weather: Weather, rng: &mut MatchRng) -> Conditions {
+    if let Some(conditions) = weather.preset() {
+        return conditions;
+    }
+    let half_wind = Conditions::MAX_WIND / 2.;
+    let wind_direction = Vec2::from_angle(rng.spread(std::f32::consts::PI));
+    Conditions {
+        wind: wind_direction * (half_wind + rng.spread(half_wind)),
+        gust_strength: 0.5 + rng.spread(0.5),
+        gust_period: 3. + rng.spread(2.),
+        humidity: 0.5 + rng.spread(0.5),
+    }
+}
+
+// push the ball around with the wind, and curve it according to its spin
+pub(crate) fn apply_conditions(
+    mut ball_query: Query<(&Velocity, &mut ExternalForce), With<Ball>>,
+    conditions: Res<Conditions>,
+    time: Res<Time>,
+    mut clock: ResMut<GustClock>,
+) {
+    clock.elapsed += time.delta_seconds();
+    let wind = conditions.wind_at(clock.elapsed);
+    for (velocity, mut force) in ball_query.iter_mut() {
+        force.force = wind + conditions.spin_force(velocity.linvel, velocity.angvel);
+    }
+}
+
+// should be run OnExit(GamePhase::Active)
+pub(crate) fn still_ball(mut ball_query: Query<&mut ExternalForce, With<Ball>>) {
+    for mut force in ball_query.iter_mut() {
+        *force = ExternalForce::default();
+    }
+}
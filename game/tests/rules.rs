@@ -0,0 +1,457 @@
+use std::time::Duration;
+
+use bevy_app::App;
+use bevy_ecs::prelude::{Commands, OnEnter, State, States, SystemSet, With};
+use bevy_rapier2d::prelude::Velocity;
+use bevy_time::TimeUpdateStrategy;
+use bevy_transform::prelude::Transform;
+
+use cricket_pong_game::{
+    actions::{Action, Actions, BatterAction, FielderAction},
+    base::{
+        ball::Ball,
+        batter::{Bat, Batter, Wicket},
+        fielder::{Fielder, FielderPosition, FielderRing},
+        power_up::{PowerUpEffect, PowerUpKind},
+        FieldingRestriction, Identity, MultiBallRules, PlayerOne, PlayerTwo, Position,
+        PowerplayRules, Score, ShotClockPenalty, ShotClockRules,
+    },
+    GamePhase, GameplayPlugin, MatchRules, Over,
+};
+use cricket_pong_support::headless_app;
+
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, States)]
+enum TestScreen {
+    #[default]
+    Match,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, SystemSet)]
+struct TestSet;
+
+// every frame is the same length, so a match plays out the same way every time
+const FRAME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+const MAX_FRAMES: usize = 1000;
+
+// player one bats first
+fn spawn_players(mut commands: Commands) {
+    commands.spawn((Position::Batter, PlayerOne, Score(0)));
+    commands.spawn((Position::Fielder, PlayerTwo, Score(0)));
+}
+
+fn match_app(rules: MatchRules) -> App {
+    let mut app = headless_app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(FRAME))
+        .insert_resource(rules)
+        .init_resource::<Actions>()
+        .add_state::<TestScreen>()
+        .add_plugins(GameplayPlugin::new(TestSet, TestScreen::Match))
+        .add_systems(OnEnter(TestScreen::Match), spawn_players);
+    app
+}
+
+fn phase(app: &App) -> GamePhase {
+    *app.world.resource::<State<GamePhase>>().get()
+}
+
+// updates the app `frames` times, with the players taking `actions` every frame
+fn run_for(app: &mut App, frames: usize, actions: &[(Identity, Action)]) {
+    for _ in 0..frames {
+        let mut queue = app.world.resource_mut::<Actions>();
+        for (player, action) in actions {
+            queue.push(*player, *action);
+        }
+        app.update();
+    }
+}
+
+// updates the app, with the players taking `actions` every frame, until `done` is true
+fn run_until(app: &mut App, actions: &[(Identity, Action)], done: impl Fn(&mut App) -> bool) {
+    for _ in 0..MAX_FRAMES {
+        run_for(app, 1, actions);
+        if done(app) {
+            return;
+        }
+    }
+    panic!("the match never reached the expected state");
+}
+
+fn batter_velocity(app: &mut App) -> Velocity {
+    *app.world
+        .query_filtered::<&Velocity, With<Batter>>()
+        .single(&app.world)
+}
+
+fn score(app: &mut App, player: Identity) -> u16 {
+    let score = match player {
+        Identity::One => app
+            .world
+            .query_filtered::<&Score, With<PlayerOne>>()
+            .single(&app.world),
+        Identity::Two => app
+            .world
+            .query_filtered::<&Score, With<PlayerTwo>>()
+            .single(&app.world),
+    };
+    score.0
+}
+
+fn stamina(app: &mut App) -> f32 {
+    app.world.query::<&Batter>().single(&app.world).stamina
+}
+
+fn recovering(app: &mut App) -> bool {
+    let batter = app.world.query::<&Batter>().single(&app.world);
+    batter.recovery_timer.is_some()
+}
+
+// how far the bat is from the wicket, and whether that leaves the wicket exposed
+fn crease(app: &mut App) -> (f32, bool) {
+    let radius = app
+        .world
+        .query_filtered::<&Transform, With<Batter>>()
+        .single(&app.world)
+        .translation
+        .truncate()
+        .length();
+    let exposed = app.world.query::<&Wicket>().single(&app.world).exposed;
+    (radius, exposed)
+}
+
+// where each outfielder stands, counter-clockwise from the top
+fn outfield(app: &mut App) -> Vec<(FielderPosition, f32)> {
+    app.world
+        .query::<&Fielder>()
+        .iter(&app.world)
+        .filter(|fielder| fielder.ring == FielderRing::Outfield)
+        .map(|fielder| (fielder.position, fielder.angle))
+        .collect()
+}
+
+// plays the next ball through, with the fielder bowling straight at the wicket
+fn bowl_at_wicket(app: &mut App) {
+    let balls = app.world.resource::<Over>().len();
+    run_until(app, &[], |app| phase(app) == GamePhase::Bowling);
+    run_until(
+        app,
+        &[(Identity::Two, Action::Fielder(FielderAction::Bowl))],
+        |app| app.world.resource::<Over>().len() > balls,
+    );
+    run_until(app, &[], |app| phase(app) == GamePhase::Bowling);
+}
+
+// how fast the bat moves in its first swing, after being wound up for `charge_frames`
+fn first_swing_speed(charge_frames: usize) -> f32 {
+    let mut app = match_app(MatchRules::default());
+    run_until(&mut app, &[], |app| phase(app) == GamePhase::Bowling);
+    run_for(
+        &mut app,
+        charge_frames,
+        &[(Identity::One, Action::Batter(BatterAction::ChargeSwing))],
+    );
+    run_for(
+        &mut app,
+        1,
+        &[(Identity::One, Action::Batter(BatterAction::SwingCCW))],
+    );
+    batter_velocity(&mut app).angvel
+}
+
+#[test]
+fn charged_swings_are_faster_until_held_too_long() {
+    let quick = first_swing_speed(0);
+    let charged = first_swing_speed(36);
+    let overcharged = first_swing_speed(120);
+    let swing_velocity = Bat::standard().swing_velocity;
+    assert!((quick - swing_velocity * Batter::MIN_SWING_POWER).abs() < 0.01);
+    assert!((charged - swing_velocity).abs() < 0.01);
+    assert!(quick < overcharged && overcharged < charged);
+}
+
+#[test]
+fn releasing_during_a_swing_lets_go_of_the_wind_up() {
+    let mut app = match_app(MatchRules::default());
+    run_until(&mut app, &[], |app| phase(app) == GamePhase::Bowling);
+    let charge = [(Identity::One, Action::Batter(BatterAction::ChargeSwing))];
+    let swing = [(Identity::One, Action::Batter(BatterAction::SwingCCW))];
+    run_for(&mut app, 1, &swing);
+
+    // winding up and letting go again before the bat has come round does nothing
+    run_for(&mut app, 5, &charge);
+    run_for(&mut app, 1, &swing);
+    let batter = app.world.query::<&Batter>().single(&app.world);
+    assert!(batter.swing_timer.is_some());
+    assert_eq!(batter.charge, None);
+
+    // so the next swing is a quick one
+    run_until(&mut app, &[], |app| batter_velocity(app).angvel == 0.);
+    run_for(&mut app, 1, &swing);
+    let swing_velocity = Bat::standard().swing_velocity;
+    assert!(
+        (batter_velocity(&mut app).angvel - swing_velocity * Batter::MIN_SWING_POWER).abs() < 0.01
+    );
+}
+
+#[test]
+fn batters_out_of_their_crease_can_be_stumped() {
+    let mut app = match_app(MatchRules::default());
+    run_until(&mut app, &[], |app| phase(app) == GamePhase::Bowling);
+    let bat = Bat::standard();
+    assert_eq!(crease(&mut app), (bat.crease_radius(), false));
+
+    // stepping in is safe, but only goes so far
+    run_for(
+        &mut app,
+        60,
+        &[(Identity::One, Action::Batter(BatterAction::StepIn))],
+    );
+    let (radius, exposed) = crease(&mut app);
+    assert!((radius - bat.min_step_radius()).abs() < 1.);
+    assert!(!exposed);
+
+    // stepping out goes further, leaving the wicket exposed
+    run_for(
+        &mut app,
+        180,
+        &[(Identity::One, Action::Batter(BatterAction::StepOut))],
+    );
+    let (radius, exposed) = crease(&mut app);
+    assert!((radius - bat.max_step_radius()).abs() < 1.);
+    assert!(exposed);
+
+    // and the ball hitting it scores for the fielder
+    run_until(
+        &mut app,
+        &[(Identity::Two, Action::Fielder(FielderAction::Bowl))],
+        |app| app.world.resource::<Over>().get(0).is_some(),
+    );
+    let ball = app.world.resource::<Over>().get(0).unwrap();
+    assert_eq!((ball.scorer, ball.value), (Identity::Two, 3));
+}
+
+#[test]
+fn swings_cost_stamina_and_need_time_to_recover_from() {
+    let mut app = match_app(MatchRules {
+        swing_recovery_time: Some(0.5),
+        ..Default::default()
+    });
+    run_until(&mut app, &[], |app| phase(app) == GamePhase::Bowling);
+    let swing = [(Identity::One, Action::Batter(BatterAction::SwingCCW))];
+
+    run_for(&mut app, 1, &swing);
+    assert!(batter_velocity(&mut app).angvel > 0.);
+    assert_eq!(
+        stamina(&mut app),
+        Batter::MAX_STAMINA - Batter::SWING_STAMINA_COST
+    );
+
+    // once the swing is over, the batter has to get their breath back
+    run_until(&mut app, &[], recovering);
+    run_for(&mut app, 1, &swing);
+    assert_eq!(batter_velocity(&mut app).angvel, 0.);
+    assert_eq!(
+        stamina(&mut app),
+        Batter::MAX_STAMINA - Batter::SWING_STAMINA_COST
+    );
+
+    run_until(&mut app, &swing, |app| batter_velocity(app).angvel > 0.);
+    assert_eq!(
+        stamina(&mut app),
+        Batter::MAX_STAMINA - Batter::SWING_STAMINA_COST * 2.
+    );
+}
+
+#[test]
+fn extra_balls_score_without_adding_deliveries() {
+    let mut app = match_app(MatchRules {
+        multi_ball: Some(MultiBallRules {
+            interval: 0.1,
+            max_balls: 3,
+        }),
+        ..Default::default()
+    });
+    run_until(&mut app, &[], |app| phase(app) == GamePhase::Bowling);
+    run_until(
+        &mut app,
+        &[(Identity::Two, Action::Fielder(FielderAction::Bowl))],
+        |app| phase(app) == GamePhase::Active,
+    );
+
+    // the delivery hits the wicket, and the extra balls bowled after it keep scoring
+    run_until(&mut app, &[], |app| {
+        app.world
+            .query::<&Score>()
+            .iter(&app.world)
+            .any(|score| score.0 > 3)
+    });
+    assert_eq!(phase(&app), GamePhase::Active);
+    assert!(app.world.query::<&Ball>().iter(&app.world).count() > 0);
+    // but the over only hears about the delivery once every ball has been scored
+    assert!(app.world.resource::<Over>().is_empty());
+}
+
+#[test]
+fn double_runs_doubles_what_its_side_scores() {
+    let mut app = match_app(MatchRules::default());
+    run_until(&mut app, &[], |app| phase(app) == GamePhase::Bowling);
+    app.world.spawn(PowerUpEffect::new(
+        PowerUpKind::DoubleRuns,
+        Position::Fielder,
+    ));
+
+    bowl_at_wicket(&mut app);
+    let ball = app.world.resource::<Over>().get(0).unwrap();
+    assert_eq!((ball.scorer, ball.value), (Identity::Two, 6));
+    assert_eq!(score(&mut app, Identity::Two), 6);
+}
+
+fn shot_clock_app(penalty: ShotClockPenalty) -> App {
+    let mut app = match_app(MatchRules {
+        shot_clock: Some(ShotClockRules {
+            seconds: 0.5,
+            penalty,
+        }),
+        ..Default::default()
+    });
+    run_until(&mut app, &[], |app| phase(app) == GamePhase::Bowling);
+    app
+}
+
+#[test]
+fn the_shot_clock_bowls_for_a_stalling_fielder() {
+    let mut app = shot_clock_app(ShotClockPenalty::AutoBowl);
+    run_for(&mut app, 20, &[]);
+    assert_eq!(phase(&app), GamePhase::Bowling);
+    run_for(&mut app, 20, &[]);
+    assert_eq!(phase(&app), GamePhase::Active);
+}
+
+#[test]
+fn the_shot_clock_gives_runs_away_while_the_fielder_stalls() {
+    let mut app = shot_clock_app(ShotClockPenalty::PenaltyRun);
+    run_for(&mut app, 20, &[]);
+    assert_eq!(score(&mut app, Identity::One), 0);
+    // a run for every time the clock runs out, without the ball ever being bowled
+    run_for(&mut app, 50, &[]);
+    assert_eq!(score(&mut app, Identity::One), 2);
+    assert_eq!(score(&mut app, Identity::Two), 0);
+    assert_eq!(phase(&app), GamePhase::Bowling);
+    assert!(app.world.resource::<Over>().is_empty());
+}
+
+const BATTER_READY: (Identity, Action) = (Identity::One, Action::Batter(BatterAction::Ready));
+const FIELDER_READY: (Identity, Action) = (Identity::Two, Action::Fielder(FielderAction::Ready));
+
+fn ready_check_app() -> App {
+    let mut app = match_app(MatchRules {
+        pause: Some(0.5),
+        ready_check: true,
+        ..Default::default()
+    });
+    run_until(&mut app, &[], |app| phase(app) == GamePhase::Preparing);
+    app
+}
+
+#[test]
+fn being_ready_doesnt_cut_the_countdown_short() {
+    let mut app = ready_check_app();
+    run_for(&mut app, 20, &[BATTER_READY, FIELDER_READY]);
+    assert_eq!(phase(&app), GamePhase::Preparing);
+    // but both sides are still ready once it runs out
+    run_for(&mut app, 20, &[]);
+    assert_eq!(phase(&app), GamePhase::Bowling);
+}
+
+#[test]
+fn the_next_ball_waits_for_both_sides_to_be_ready() {
+    let mut app = ready_check_app();
+    run_for(&mut app, 60, &[BATTER_READY]);
+    assert_eq!(phase(&app), GamePhase::Preparing);
+    run_for(&mut app, 2, &[FIELDER_READY]);
+    assert_eq!(phase(&app), GamePhase::Bowling);
+}
+
+#[test]
+fn the_next_ball_only_waits_out_the_countdown_without_a_ready_check() {
+    let mut app = match_app(MatchRules {
+        pause: Some(0.5),
+        ..Default::default()
+    });
+    run_until(&mut app, &[], |app| phase(app) == GamePhase::Preparing);
+    run_for(&mut app, 20, &[]);
+    assert_eq!(phase(&app), GamePhase::Preparing);
+    run_for(&mut app, 20, &[]);
+    assert_eq!(phase(&app), GamePhase::Bowling);
+}
+
+#[test]
+fn players_only_act_for_the_position_they_play() {
+    let mut app = match_app(MatchRules::default());
+    run_until(&mut app, &[], |app| phase(app) == GamePhase::Bowling);
+
+    // the batter can't bowl, and the fielder can't move the bat
+    let out_of_position = [
+        (Identity::One, Action::Fielder(FielderAction::Bowl)),
+        (Identity::Two, Action::Batter(BatterAction::MoveCW)),
+    ];
+    run_for(&mut app, 30, &out_of_position);
+    assert_eq!(phase(&app), GamePhase::Bowling);
+    assert_eq!(batter_velocity(&mut app).angvel, 0.);
+
+    // but each can act for their own side
+    run_until(
+        &mut app,
+        &[(Identity::One, Action::Batter(BatterAction::MoveCW))],
+        |app| batter_velocity(app).angvel != 0.,
+    );
+    run_until(
+        &mut app,
+        &[(Identity::Two, Action::Fielder(FielderAction::Bowl))],
+        |app| phase(app) == GamePhase::Active,
+    );
+}
+
+#[test]
+fn powerplay_outfield_is_manned_again_where_it_was_left() {
+    let mut app = match_app(MatchRules {
+        powerplay: Some(PowerplayRules {
+            balls: 1,
+            restriction: FieldingRestriction::Fielders(2),
+        }),
+        ..Default::default()
+    });
+    run_until(&mut app, &[], |app| phase(app) == GamePhase::Bowling);
+    let mut positions = outfield(&mut app)
+        .into_iter()
+        .map(|(position, _)| position)
+        .collect::<Vec<_>>();
+    positions.sort_by_key(|position| *position as u8);
+    assert_eq!(positions, [FielderPosition::Top, FielderPosition::Bottom]);
+
+    // the outfield turns away from where it started before the powerplay ends
+    run_for(
+        &mut app,
+        30,
+        &[(
+            Identity::Two,
+            Action::Fielder(FielderAction::MoveOutfieldCCW),
+        )],
+    );
+    let (_, top_angle) = outfield(&mut app)
+        .into_iter()
+        .find(|(position, _)| *position == FielderPosition::Top)
+        .unwrap();
+    assert!(top_angle > 0.1);
+
+    bowl_at_wicket(&mut app);
+    let outfield = outfield(&mut app);
+    assert_eq!(outfield.len(), FielderPosition::ALL.len());
+    for (position, angle) in outfield {
+        let expected = (position.angle() + top_angle).rem_euclid(std::f32::consts::TAU);
+        let turned = angle.rem_euclid(std::f32::consts::TAU);
+        assert!(
+            (turned - expected).abs() < 0.001,
+            "{position:?} at {turned}"
+        );
+    }
+}
@@ -40,12 +40,20 @@ where
     GameState: States + Copy,
 {
     fn build(&self, app: &mut App) {
-        app.add_plugins(objects::ObjectGraphicsPlugin)
-            .add_plugins(ui::GameUIPlugin::new(
-                self.active_screen,
-                self.return_screen,
-                self.gameover_state,
-            ))
-            .add_systems(Startup, setup_camera);
+        // object graphics and the camera are shared by every screen this plugin is added for
+        if !app.is_plugin_added::<objects::ObjectGraphicsPlugin>() {
+            app.add_plugins(objects::ObjectGraphicsPlugin)
+                .add_systems(Startup, setup_camera);
+        }
+        app.add_plugins(ui::GameUIPlugin::new(
+            self.active_screen,
+            self.return_screen,
+            self.gameover_state,
+        ));
+    }
+
+    // one GraphicsPlugin is added for each screen that shows a game
+    fn is_unique(&self) -> bool {
+        false
     }
 }
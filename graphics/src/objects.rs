@@ -1,49 +1,75 @@
 use bevy::{
     prelude::{
-        shape, Added, App, Assets, Color, Commands, Entity, IntoSystemConfigs, Mesh, Plugin,
-        PostUpdate, Query, ResMut, SystemSet, Transform, Vec2,
+        shape, Added, App, Assets, BuildChildren, Changed, Color, Commands, Component, Entity,
+        Handle, IntoSystemConfigs, Mesh, Parent, Plugin, PostUpdate, Query, Res, ResMut, SystemSet,
+        Transform, Vec2, Vec3, With,
     },
-    sprite::{ColorMaterial, MaterialMesh2dBundle},
+    sprite::{ColorMaterial, MaterialMesh2dBundle, Mesh2dHandle},
 };
 
 use bevy_prototype_lyon::prelude::{
-    shapes, Fill, GeometryBuilder, ShapeBundle, ShapePlugin, Stroke,
+    shapes, Fill, GeometryBuilder, Path, PathBuilder, ShapeBundle, ShapePlugin, Stroke,
 };
 
 use cricket_pong_base::{
-    ball::Ball,
-    batter::{Batter, Wicket},
+    ball::{Ball, BallKind},
+    batter::{Bat, Batter, Wicket},
     fielder::{Boundary, Fielder, FielderRing},
+    obstacle::{Obstacle, ObstacleKind},
+    power_up::{PowerUp, PowerUpKind},
+    Conditions, GroundShape, MatchRules, WindVane,
 };
 
+fn ball_color(kind: BallKind) -> Color {
+    match kind {
+        BallKind::Leather => Color::MIDNIGHT_BLUE,
+        BallKind::Tennis => Color::YELLOW_GREEN,
+        BallKind::Heavy => Color::DARK_GRAY,
+        BallKind::Bouncy => Color::FUCHSIA,
+    }
+}
+
 fn setup_ball_shape(
     mut commands: Commands,
-    added_ball_query: Query<(Entity, &Transform), Added<Ball>>,
+    added_ball_query: Query<(Entity, &Transform, &Ball), Added<Ball>>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
-    for (entity, transform) in added_ball_query.iter() {
+    for (entity, transform, ball) in added_ball_query.iter() {
         commands.entity(entity).insert(MaterialMesh2dBundle {
-            mesh: meshes.add(shape::Circle::new(Ball::RADIUS).into()).into(),
-            material: materials.add(Color::MIDNIGHT_BLUE.into()),
+            mesh: meshes
+                .add(shape::Circle::new(ball.kind.radius()).into())
+                .into(),
+            material: materials.add(ball_color(ball.kind).into()),
             transform: *transform,
             ..Default::default()
         });
     }
 }
 
+// the ground's curve, drawn at the given nominal radius
+fn ground_path(ground: &GroundShape, radius: f32) -> Path {
+    match ground {
+        GroundShape::Circle => GeometryBuilder::build_as(&shapes::Circle {
+            radius,
+            ..Default::default()
+        }),
+        _ => GeometryBuilder::build_as(&shapes::Polygon {
+            points: ground.outline(radius),
+            closed: true,
+        }),
+    }
+}
+
 fn setup_field_shape(
     mut commands: Commands,
     added_fielder_ring_query: Query<(Entity, &FielderRing), Added<FielderRing>>,
+    rules: Res<MatchRules>,
 ) {
     for (entity, fielder_ring) in added_fielder_ring_query.iter() {
-        let shape = shapes::Circle {
-            radius: fielder_ring.radius(),
-            ..Default::default()
-        };
         commands.entity(entity).insert((
             ShapeBundle {
-                path: GeometryBuilder::build_as(&shape),
+                path: ground_path(&rules.ground, fielder_ring.radius()),
                 ..Default::default()
             },
             Stroke::new(Color::BLACK, 4.),
@@ -51,6 +77,14 @@ fn setup_field_shape(
     }
 }
 
+// the reach the fielder's mesh was last built for
+#[derive(Component)]
+struct DrawnReach(f32);
+
+fn fielder_mesh(fielder: &Fielder) -> Mesh {
+    shape::Quad::new(Vec2::new(fielder.hwidth() * 2., Fielder::HDEPTH * 2.)).into()
+}
+
 fn setup_fielder_shape(
     mut commands: Commands,
     added_fielder_query: Query<(Entity, &Transform, &Fielder), Added<Fielder>>,
@@ -58,31 +92,39 @@ fn setup_fielder_shape(
     mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
     for (entity, transform, fielder) in added_fielder_query.iter() {
-        commands.entity(entity).insert(MaterialMesh2dBundle {
-            mesh: meshes
-                .add(
-                    shape::Quad::new(Vec2::new(fielder.hwidth() * 2., Fielder::HDEPTH * 2.)).into(),
-                )
-                .into(),
-            material: materials.add(Color::AQUAMARINE.into()),
-            transform: *transform,
-            ..Default::default()
-        });
+        commands.entity(entity).insert((
+            MaterialMesh2dBundle {
+                mesh: meshes.add(fielder_mesh(fielder)).into(),
+                material: materials.add(Color::AQUAMARINE.into()),
+                transform: *transform,
+                ..Default::default()
+            },
+            DrawnReach(fielder.reach),
+        ));
+    }
+}
+
+fn update_fielder_shape(
+    mut fielder_query: Query<(&Fielder, &mut DrawnReach, &mut Mesh2dHandle)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for (fielder, mut drawn_reach, mut mesh) in fielder_query.iter_mut() {
+        if drawn_reach.0 != fielder.reach {
+            drawn_reach.0 = fielder.reach;
+            *mesh = meshes.add(fielder_mesh(fielder)).into();
+        }
     }
 }
 
 fn setup_boundary_shape(
     mut commands: Commands,
     added_boundary_query: Query<Entity, Added<Boundary>>,
+    rules: Res<MatchRules>,
 ) {
     for entity in added_boundary_query.iter() {
-        let shape = shapes::Circle {
-            radius: Boundary::RADIUS,
-            ..Default::default()
-        };
         commands.entity(entity).insert((
             ShapeBundle {
-                path: GeometryBuilder::build_as(&shape),
+                path: ground_path(&rules.ground, Boundary::RADIUS),
                 ..Default::default()
             },
             Stroke::new(Color::DARK_GREEN, 8.),
@@ -92,11 +134,11 @@ fn setup_boundary_shape(
 
 fn setup_batter_shape(
     mut commands: Commands,
-    added_batter_query: Query<(Entity, &Transform), Added<Batter>>,
+    added_batter_query: Query<(Entity, &Transform, &Bat), Added<Batter>>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
-    for (entity, transform) in added_batter_query.iter() {
+    for (entity, transform, bat) in added_batter_query.iter() {
         let shape = shapes::Circle {
             radius: Batter::RADIUS,
             ..Default::default()
@@ -110,21 +152,101 @@ fn setup_batter_shape(
             Stroke::new(Color::BLACK, 4.),
         ));
         // bat itself
-        commands.entity(entity).insert((MaterialMesh2dBundle {
-            mesh: meshes
-                .add(shape::Quad::new(Vec2::new(Batter::HWIDTH * 2., Batter::HDEPTH * 2.)).into())
-                .into(),
-            material: materials.add(Color::rgb(0.59, 0.29, 0.).into()),
-            transform: *transform,
-            ..Default::default()
-        },));
+        commands
+            .entity(entity)
+            .insert((MaterialMesh2dBundle {
+                mesh: meshes.add(bat_mesh(bat)).into(),
+                material: materials.add(Color::rgb(0.59, 0.29, 0.).into()),
+                transform: *transform,
+                ..Default::default()
+            },))
+            .with_children(|parent| {
+                // charge meter running alongside the bat
+                // its mesh is one unit long, and is stretched out to the bat's length as it fills
+                parent.spawn((
+                    ChargeMeter,
+                    MaterialMesh2dBundle {
+                        mesh: meshes
+                            .add(shape::Quad::new(Vec2::new(1., ChargeMeter::HDEPTH * 2.)).into())
+                            .into(),
+                        material: materials.add(ChargeMeter::COLOR.into()),
+                        transform: Transform::from_xyz(0., ChargeMeter::offset(bat), 0.)
+                            .with_scale(Vec3::new(0., 1., 1.)),
+                        ..Default::default()
+                    },
+                ));
+            });
     }
 }
 
-fn setup_wicket_shape(mut commands: Commands, added_wicket_query: Query<Entity, Added<Wicket>>) {
-    for entity in added_wicket_query.iter() {
+fn bat_mesh(bat: &Bat) -> Mesh {
+    shape::Quad::new(Vec2::new(bat.hwidth * 2., bat.hdepth * 2.)).into()
+}
+
+fn update_bat_shape(
+    mut bat_query: Query<(&Bat, &mut Mesh2dHandle), Changed<Bat>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for (bat, mut mesh) in bat_query.iter_mut() {
+        *mesh = meshes.add(bat_mesh(bat)).into();
+    }
+}
+
+#[derive(Component)]
+struct ChargeMeter;
+
+impl ChargeMeter {
+    const HDEPTH: f32 = 2.;
+    const COLOR: Color = Color::YELLOW;
+    const OVERCHARGED_COLOR: Color = Color::RED;
+
+    fn offset(bat: &Bat) -> f32 {
+        -(bat.hdepth + Self::HDEPTH * 2.)
+    }
+}
+
+fn update_charge_meter(
+    batter_query: Query<(&Batter, &Bat)>,
+    mut meter_query: Query<(&Parent, &mut Transform, &Handle<ColorMaterial>), With<ChargeMeter>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (parent, mut transform, material) in meter_query.iter_mut() {
+        let Ok((batter, bat)) = batter_query.get(parent.get()) else { continue };
+        let charge = batter.charge.unwrap_or_default();
+        transform.scale.x = (charge / Batter::FULL_CHARGE_TIME).min(1.) * bat.hwidth * 2.;
+        transform.translation.y = ChargeMeter::offset(bat);
+
+        let color = if batter.is_overcharged() {
+            ChargeMeter::OVERCHARGED_COLOR
+        } else {
+            ChargeMeter::COLOR
+        };
+        if materials
+            .get(material)
+            .is_some_and(|material| material.color != color)
+        {
+            if let Some(material) = materials.get_mut(material) {
+                material.color = color;
+            }
+        }
+    }
+}
+
+fn wicket_fill(wicket: &Wicket) -> Fill {
+    if wicket.exposed {
+        Fill::color(Color::rgba(0.8, 0.1, 0.1, 0.5))
+    } else {
+        Fill::color(Color::DARK_GREEN)
+    }
+}
+
+fn setup_wicket_shape(
+    mut commands: Commands,
+    added_wicket_query: Query<(Entity, &Wicket), Added<Wicket>>,
+) {
+    for (entity, wicket) in added_wicket_query.iter() {
         let shape = shapes::Circle {
-            radius: Wicket::RADIUS,
+            radius: wicket.radius(),
             ..Default::default()
         };
         commands.entity(entity).insert((
@@ -133,11 +255,142 @@ fn setup_wicket_shape(mut commands: Commands, added_wicket_query: Query<Entity,
                 ..Default::default()
             },
             Stroke::new(Color::BLACK, 2.),
-            Fill::color(Color::DARK_GREEN),
+            wicket_fill(wicket),
+        ));
+    }
+}
+
+fn update_wicket_shape(mut wicket_query: Query<(&Wicket, &mut Path, &mut Fill), Changed<Wicket>>) {
+    for (wicket, mut path, mut fill) in wicket_query.iter_mut() {
+        let shape = shapes::Circle {
+            radius: wicket.radius(),
+            ..Default::default()
+        };
+        *path = GeometryBuilder::build_as(&shape);
+        *fill = wicket_fill(wicket);
+    }
+}
+
+fn obstacle_fill(kind: ObstacleKind) -> Fill {
+    match kind {
+        ObstacleKind::Pillar => Fill::color(Color::GRAY),
+        ObstacleKind::Bumper => Fill::color(Color::ORANGE_RED),
+        ObstacleKind::SlowZone => Fill::color(Color::rgba(0.4, 0.3, 0.1, 0.5)),
+    }
+}
+
+fn setup_obstacle_shape(
+    mut commands: Commands,
+    added_obstacle_query: Query<(Entity, &Transform, &Obstacle), Added<Obstacle>>,
+) {
+    for (entity, transform, obstacle) in added_obstacle_query.iter() {
+        let shape = shapes::Circle {
+            radius: obstacle.radius,
+            ..Default::default()
+        };
+        // bonus obstacles are picked out in gold
+        let stroke = if obstacle.bonus_runs.is_some() {
+            Stroke::new(Color::GOLD, 3.)
+        } else {
+            Stroke::new(Color::BLACK, 2.)
+        };
+        commands.entity(entity).insert((
+            ShapeBundle {
+                path: GeometryBuilder::build_as(&shape),
+                transform: *transform,
+                ..Default::default()
+            },
+            stroke,
+            obstacle_fill(obstacle.kind),
+        ));
+    }
+}
+
+fn power_up_color(kind: PowerUpKind) -> Color {
+    match kind {
+        PowerUpKind::WideBat => Color::LIME_GREEN,
+        PowerUpKind::SlowBall => Color::TEAL,
+        PowerUpKind::FrozenRing => Color::AZURE,
+        PowerUpKind::DoubleRuns => Color::GOLD,
+    }
+}
+
+fn setup_power_up_shape(
+    mut commands: Commands,
+    added_power_up_query: Query<(Entity, &Transform, &PowerUp), Added<PowerUp>>,
+) {
+    for (entity, transform, power_up) in added_power_up_query.iter() {
+        let shape = shapes::Circle {
+            radius: PowerUp::RADIUS,
+            ..Default::default()
+        };
+        commands.entity(entity).insert((
+            ShapeBundle {
+                path: GeometryBuilder::build_as(&shape),
+                transform: *transform,
+                ..Default::default()
+            },
+            Stroke::new(Color::WHITE, 2.),
+            Fill::color(power_up_color(power_up.kind)),
         ));
     }
 }
 
+// drawn off to the side of the field, pointing the way the wind blows
+fn wind_vane_path(conditions: &Conditions) -> Path {
+    const CENTER: Vec2 = Vec2::new(-Boundary::RADIUS - 100., Boundary::RADIUS - 50.);
+    const MAX_LENGTH: f32 = 80.;
+    const HEAD_SIZE: f32 = 12.;
+
+    let strength = (conditions.wind.length() / Conditions::MAX_WIND).min(1.);
+    let direction = conditions.wind.normalize_or_zero();
+    if direction == Vec2::ZERO {
+        // no wind at all, so just mark the spot
+        return GeometryBuilder::build_as(&shapes::Circle {
+            radius: 4.,
+            center: CENTER,
+        });
+    }
+    let length = MAX_LENGTH * strength.max(0.2);
+    let tail = CENTER - direction * length / 2.;
+    let head = CENTER + direction * length / 2.;
+    let mut builder = PathBuilder::new();
+    builder.move_to(tail);
+    builder.line_to(head);
+    builder.move_to(head - direction * HEAD_SIZE + direction.perp() * HEAD_SIZE / 2.);
+    builder.line_to(head);
+    builder.line_to(head - direction * HEAD_SIZE - direction.perp() * HEAD_SIZE / 2.);
+    builder.build()
+}
+
+fn setup_wind_vane_shape(
+    mut commands: Commands,
+    added_wind_vane_query: Query<Entity, Added<WindVane>>,
+    conditions: Res<Conditions>,
+) {
+    for entity in added_wind_vane_query.iter() {
+        commands.entity(entity).insert((
+            ShapeBundle {
+                path: wind_vane_path(&conditions),
+                ..Default::default()
+            },
+            Stroke::new(Color::WHITE, 4.),
+        ));
+    }
+}
+
+fn update_wind_vane(
+    mut wind_vane_query: Query<&mut Path, With<WindVane>>,
+    conditions: Res<Conditions>,
+) {
+    if !conditions.is_changed() {
+        return;
+    }
+    for mut path in wind_vane_query.iter_mut() {
+        *path = wind_vane_path(&conditions);
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, SystemSet)]
 pub struct ObjectGraphicsSet;
 
@@ -151,9 +404,17 @@ impl Plugin for ObjectGraphicsPlugin {
                 setup_ball_shape,
                 setup_field_shape,
                 setup_fielder_shape,
+                update_fielder_shape,
                 setup_boundary_shape,
                 setup_batter_shape,
                 setup_wicket_shape,
+                update_wicket_shape,
+                setup_obstacle_shape,
+                setup_power_up_shape,
+                update_bat_shape,
+                update_charge_meter,
+                setup_wind_vane_shape,
+                update_wind_vane,
             )
                 .in_set(ObjectGraphicsSet),
         );
@@ -1,15 +1,18 @@
 use bevy::{
     prelude::{
         in_state, Added, AlignItems, App, BackgroundColor, BuildChildren, ButtonBundle, Changed,
-        ChildBuilder, Color, Commands, Component, DespawnRecursiveExt, Display, Entity,
+        ChildBuilder, Color, Commands, Component, Condition, DespawnRecursiveExt, Display, Entity,
         FlexDirection, GridAutoFlow, IntoSystemConfigs, JustifyContent, NextState, NodeBundle,
-        OnEnter, OnExit, Plugin, PositionType, PostUpdate, Query, Res, ResMut, States, Style,
-        SystemSet, Text, TextBundle, TextStyle, UiRect, Val, With, Without,
+        OnEnter, OnExit, Plugin, PositionType, PostUpdate, Query, Res, ResMut, State, States,
+        Style, SystemSet, Text, TextBundle, TextStyle, UiRect, Val, With, Without,
     },
     ui::{BorderColor, GridPlacement, GridTrack, Interaction},
 };
 
-use cricket_pong_base::{Identity, Over, PlayerOne, PlayerTwo, Position, Score};
+use cricket_pong_base::{
+    batter::Batter, power_up::PowerUpEffect, BallCountdown, Forfeit, Identity, MatchPause,
+    MatchRules, Over, PlayerOne, PlayerTwo, Position, Score, ShotClock,
+};
 
 #[derive(Component)]
 struct Scoreboard;
@@ -20,6 +23,34 @@ struct OverScoreboard;
 #[derive(Component)]
 struct GameoverPanel;
 
+#[derive(Component)]
+struct StaminaPanel;
+
+#[derive(Component)]
+struct StaminaBar;
+
+#[derive(Component)]
+struct PowerUpPanel;
+
+#[derive(Component)]
+struct PowerplayBadge;
+
+#[derive(Component)]
+struct ShotClockPanel;
+
+#[derive(Component)]
+struct PauseBanner;
+
+#[derive(Component)]
+struct ShotClockTracker {
+    pub style: TextStyle,
+}
+
+#[derive(Component)]
+struct PowerUpTracker {
+    pub style: TextStyle,
+}
+
 #[derive(Component)]
 struct ScoreTracker {
     pub player: Identity,
@@ -118,6 +149,7 @@ fn spawn_scoreboard(
     spawn_player_scoreboard(&mut commands, score_two, position_two, Identity::Two);
 }
 
+#[allow(clippy::type_complexity)]
 fn update_scoreboard(
     player_one_query: Query<(&Score, &Position), (With<PlayerOne>, Without<PlayerTwo>)>,
     player_two_query: Query<(&Score, &Position), (With<PlayerTwo>, Without<PlayerOne>)>,
@@ -223,6 +255,310 @@ fn update_over_tracker(
     }
 }
 
+fn spawn_stamina_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            StaminaPanel,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    justify_content: JustifyContent::Center,
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(4.),
+                        padding: UiRect::all(Val::Px(8.)),
+                        ..Default::default()
+                    },
+                    background_color: BackgroundColor(Color::AZURE),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Stamina",
+                        TextStyle {
+                            color: Color::BLACK,
+                            font_size: 16.,
+                            ..Default::default()
+                        },
+                    ));
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                width: Val::Px(120.),
+                                height: Val::Px(12.),
+                                border: UiRect::all(Val::Px(2.)),
+                                ..Default::default()
+                            },
+                            border_color: BorderColor(Color::BLACK),
+                            background_color: BackgroundColor(Color::WHITE),
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn((
+                                StaminaBar,
+                                NodeBundle {
+                                    style: Style {
+                                        width: Val::Percent(100.),
+                                        height: Val::Percent(100.),
+                                        ..Default::default()
+                                    },
+                                    background_color: BackgroundColor(Color::LIME_GREEN),
+                                    ..Default::default()
+                                },
+                            ));
+                        });
+                });
+        });
+}
+
+fn update_stamina_panel(
+    batter_query: Query<&Batter>,
+    mut bar_query: Query<(&mut Style, &mut BackgroundColor), With<StaminaBar>>,
+) {
+    let Ok(batter) = batter_query.get_single() else { return };
+    for (mut style, mut background_color) in bar_query.iter_mut() {
+        style.width = Val::Percent(batter.stamina / Batter::MAX_STAMINA * 100.);
+        background_color.0 = if batter.is_tired() {
+            Color::ORANGE_RED
+        } else {
+            Color::LIME_GREEN
+        };
+    }
+}
+
+fn spawn_power_up_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            PowerUpPanel,
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(12.)),
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.),
+                    right: Val::Px(0.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ))
+        .with_children(|parent| {
+            let style = TextStyle {
+                font_size: 20.,
+                color: Color::GOLD,
+                ..Default::default()
+            };
+            parent.spawn((
+                PowerUpTracker {
+                    style: style.clone(),
+                },
+                TextBundle::from_section("", style),
+            ));
+        });
+}
+
+// lists every active power-up effect, who has it and how long it has left
+fn update_power_up_panel(
+    effect_query: Query<&PowerUpEffect>,
+    mut text_query: Query<(&PowerUpTracker, &mut Text)>,
+) {
+    let lines = effect_query
+        .iter()
+        .map(|effect| {
+            format!(
+                "{} ({:?}) {:.0}s",
+                effect.kind,
+                effect.side,
+                effect.remaining.ceil()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    for (tracker, mut text) in text_query.iter_mut() {
+        if text.sections.first().map(|section| &section.value) != Some(&lines) {
+            *text = Text::from_section(lines.clone(), tracker.style.clone());
+        }
+    }
+}
+
+fn spawn_powerplay_badge(mut commands: Commands) {
+    commands
+        .spawn((
+            PowerplayBadge,
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    padding: UiRect::all(Val::Px(8.)),
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(0.),
+                    right: Val::Px(0.),
+                    ..Default::default()
+                },
+                background_color: BackgroundColor(Color::ORANGE_RED),
+                ..Default::default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "POWERPLAY",
+                TextStyle {
+                    font_size: 28.,
+                    color: Color::WHITE,
+                    ..Default::default()
+                },
+            ));
+        });
+}
+
+fn update_powerplay_badge(
+    mut badge_query: Query<&mut Style, With<PowerplayBadge>>,
+    rules: Res<MatchRules>,
+    over: Res<Over>,
+) {
+    let display = if rules.is_powerplay(&over) {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    for mut style in badge_query.iter_mut() {
+        if style.display != display {
+            style.display = display;
+        }
+    }
+}
+
+fn spawn_pause_banner(mut commands: Commands) {
+    commands
+        .spawn((
+            PauseBanner,
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    position_type: PositionType::Absolute,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        padding: UiRect::all(Val::Px(12.)),
+                        ..Default::default()
+                    },
+                    background_color: BackgroundColor(Color::rgba(0., 0., 0., 0.7)),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Waiting for a player to reconnect",
+                        TextStyle {
+                            font_size: 28.,
+                            color: Color::WHITE,
+                            ..Default::default()
+                        },
+                    ));
+                });
+        });
+}
+
+fn update_pause_banner(
+    mut banner_query: Query<&mut Style, With<PauseBanner>>,
+    pause: Res<State<MatchPause>>,
+) {
+    let display = match pause.get() {
+        MatchPause::Paused => Display::Flex,
+        MatchPause::Running => Display::None,
+    };
+    for mut style in banner_query.iter_mut() {
+        if style.display != display {
+            style.display = display;
+        }
+    }
+}
+
+fn spawn_shot_clock_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            ShotClockPanel,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    justify_content: JustifyContent::Center,
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(0.),
+                    padding: UiRect::all(Val::Px(12.)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ))
+        .with_children(|parent| {
+            let style = TextStyle {
+                font_size: 28.,
+                color: Color::WHITE,
+                ..Default::default()
+            };
+            parent.spawn((
+                ShotClockTracker {
+                    style: style.clone(),
+                },
+                TextBundle::from_section("", style),
+            ));
+        });
+}
+
+// counts down to the next ball while waiting between balls,
+// then the time the fielding side has left to bowl it
+fn update_shot_clock_panel(
+    mut text_query: Query<(&ShotClockTracker, &mut Text)>,
+    clock: Res<ShotClock>,
+    countdown: Res<BallCountdown>,
+    rules: Res<MatchRules>,
+) {
+    if !clock.is_changed() && !countdown.is_changed() {
+        return;
+    }
+    let value = if let Some(remaining) = countdown.remaining {
+        if remaining > 0. {
+            format!("Next ball in {:.0}", remaining.ceil())
+        } else if rules.ready_check {
+            let waiting_on = match (countdown.batter_ready, countdown.fielder_ready) {
+                (false, false) => "both sides",
+                (false, true) => "the batter",
+                _ => "the fielders",
+            };
+            format!("Waiting for {} to ready up", waiting_on)
+        } else {
+            String::new()
+        }
+    } else {
+        clock
+            .remaining
+            .map(|remaining| format!("Bowl in {:.0}", remaining.max(0.).ceil()))
+            .unwrap_or_default()
+    };
+    for (tracker, mut text) in text_query.iter_mut() {
+        *text = Text::from_section(value.clone(), tracker.style.clone());
+    }
+}
+
 #[derive(Component)]
 struct ReturnButton;
 
@@ -230,6 +566,7 @@ fn spawn_gameover_panel(
     mut commands: Commands,
     player_one_query: Query<&Score, With<PlayerOne>>,
     player_two_query: Query<&Score, With<PlayerTwo>>,
+    forfeit: Res<Forfeit>,
 ) {
     let player_one_score = player_one_query.single();
     let player_two_score = player_two_query.single();
@@ -274,10 +611,11 @@ fn spawn_gameover_panel(
                     ..Default::default()
                 })
                 .with_children(|parent| {
-                    let winner_text = if let Some(winner) = winner {
-                        format!("Player {} wins!", winner)
-                    } else {
-                        "It's a tie!".to_string()
+                    // a forfeit decides the match, whatever the score
+                    let winner_text = match (forfeit.winner(), winner) {
+                        (Some(winner), _) => format!("Player {} wins by forfeit!", winner),
+                        (None, Some(winner)) => format!("Player {} wins!", winner),
+                        (None, None) => "It's a tie!".to_string(),
                     };
                     parent.spawn(TextBundle::from_section(
                         winner_text,
@@ -351,11 +689,17 @@ fn build_detect_return_selection_system<AppScreen: States + Copy>(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn cleanup_ui(
     mut commands: Commands,
     scoreboard_query: Query<Entity, With<Scoreboard>>,
     over_scoreboard_query: Query<Entity, With<OverScoreboard>>,
     gameover_panel_query: Query<Entity, With<GameoverPanel>>,
+    stamina_panel_query: Query<Entity, With<StaminaPanel>>,
+    power_up_panel_query: Query<Entity, With<PowerUpPanel>>,
+    powerplay_badge_query: Query<Entity, With<PowerplayBadge>>,
+    shot_clock_panel_query: Query<Entity, With<ShotClockPanel>>,
+    pause_banner_query: Query<Entity, With<PauseBanner>>,
 ) {
     for entity in scoreboard_query.iter() {
         commands.entity(entity).despawn_recursive();
@@ -366,6 +710,21 @@ fn cleanup_ui(
     for entity in gameover_panel_query.iter() {
         commands.entity(entity).despawn_recursive();
     }
+    for entity in stamina_panel_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in power_up_panel_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in powerplay_badge_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in shot_clock_panel_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in pause_banner_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, SystemSet)]
@@ -401,17 +760,46 @@ where
     GameState: States + Copy,
 {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(self.active_screen), spawn_over_tracker)
-            .add_systems(
-                PostUpdate,
-                (spawn_scoreboard, update_scoreboard, update_over_tracker).in_set(GameUISet),
+        app.add_systems(
+            OnEnter(self.active_screen),
+            (
+                spawn_over_tracker,
+                spawn_stamina_panel,
+                spawn_power_up_panel,
+                spawn_powerplay_badge,
+                spawn_shot_clock_panel,
+                spawn_pause_banner,
+            ),
+        )
+        .add_systems(
+            PostUpdate,
+            (
+                spawn_scoreboard,
+                update_scoreboard,
+                update_over_tracker,
+                update_stamina_panel,
+                update_power_up_panel,
+                update_powerplay_badge,
+                update_shot_clock_panel,
+                update_pause_banner,
             )
-            .add_systems(OnEnter(self.gameover_state), spawn_gameover_panel)
-            .add_systems(
-                PostUpdate,
-                build_detect_return_selection_system(self.return_screen)
-                    .run_if(in_state(self.gameover_state)),
-            )
-            .add_systems(OnExit(self.active_screen), cleanup_ui);
+                .run_if(in_state(self.active_screen))
+                .in_set(GameUISet),
+        )
+        .add_systems(
+            OnEnter(self.gameover_state),
+            spawn_gameover_panel.run_if(in_state(self.active_screen)),
+        )
+        .add_systems(
+            PostUpdate,
+            build_detect_return_selection_system(self.return_screen)
+                .run_if(in_state(self.gameover_state).and_then(in_state(self.active_screen))),
+        )
+        .add_systems(OnExit(self.active_screen), cleanup_ui);
+    }
+
+    // one GameUIPlugin is added for each screen that shows a game
+    fn is_unique(&self) -> bool {
+        false
     }
 }
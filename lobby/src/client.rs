@@ -0,0 +1,170 @@
+use bevy::prelude::{
+    in_state, App, IntoSystemConfigs, OnEnter, OnExit, Plugin, Res, ResMut, Resource, States, Time,
+    Update,
+};
+
+use cricket_pong_net::{Connection, ServerAddress, SessionToken};
+
+use crate::protocol::{LobbyRequest, LobbyResponse, RoomCode, RoomState};
+
+// The lobby to find a room on, which must be set before the lobby screen is entered.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct LobbyAddress(pub ServerAddress);
+
+// Whether to open a new room or join someone else's, which must also be set before
+// the lobby screen is entered.
+#[derive(Resource, Clone, Debug, PartialEq, Eq)]
+pub enum RoomChoice {
+    Host,
+    Join(RoomCode),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LobbyError {
+    // the lobby couldn't be reached at the address given
+    Unreachable,
+    NotFound,
+    Full,
+}
+
+// This client's place in the lobby.
+#[derive(Resource, Default)]
+pub struct LobbyClient {
+    connection: Option<Connection>,
+    choice: Option<RoomChoice>,
+    room: Option<RoomState>,
+    ready: bool,
+    error: Option<LobbyError>,
+    // time since the last request was sent
+    since_request: f32,
+}
+
+impl LobbyClient {
+    // how often the lobby is reminded of what this client wants
+    const REQUEST_INTERVAL: f32 = 0.5;
+
+    pub fn room(&self) -> Option<&RoomState> {
+        self.room.as_ref()
+    }
+
+    pub fn error(&self) -> Option<LobbyError> {
+        self.error
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready
+    }
+
+    pub fn set_ready(&mut self, ready: bool) {
+        if self.ready != ready {
+            self.ready = ready;
+            self.send_request();
+        }
+    }
+
+    // the game server to play the match on, once everyone in the room is ready
+    pub fn match_server(&self) -> Option<ServerAddress> {
+        self.room.as_ref()?.server.as_ref()?.parse().ok()
+    }
+
+    // what proves to the game server which seat is this client's
+    pub fn match_token(&self) -> Option<SessionToken> {
+        self.room.as_ref()?.token.map(SessionToken)
+    }
+
+    // the request that takes this client from where it is to where it wants to be
+    fn request(&self) -> Option<LobbyRequest> {
+        if self.room.is_some() {
+            return Some(LobbyRequest::Ready(self.ready));
+        }
+        match self.choice.as_ref()? {
+            RoomChoice::Host => Some(LobbyRequest::Host),
+            RoomChoice::Join(code) => Some(LobbyRequest::Join(code.clone())),
+        }
+    }
+
+    fn send_request(&mut self) {
+        self.since_request = 0.;
+        let Some(request) = self.request() else { return };
+        if let Some(connection) = &mut self.connection {
+            connection.send(&request);
+        }
+    }
+}
+
+// should be run OnEnter(active_screen)
+fn enter_lobby(
+    mut client: ResMut<LobbyClient>,
+    address: Option<Res<LobbyAddress>>,
+    choice: Option<Res<RoomChoice>>,
+) {
+    let (Some(address), Some(choice)) = (address, choice) else { return };
+    *client = LobbyClient {
+        choice: Some(choice.clone()),
+        ..Default::default()
+    };
+    match Connection::open(&address.0) {
+        Ok(connection) => client.connection = Some(connection),
+        Err(_) => client.error = Some(LobbyError::Unreachable),
+    }
+    client.send_request();
+}
+
+// should be run OnExit(active_screen)
+fn leave_lobby(mut client: ResMut<LobbyClient>) {
+    if let Some(connection) = &mut client.connection {
+        connection.send(&LobbyRequest::Leave);
+    }
+    *client = LobbyClient::default();
+}
+
+fn update_lobby_client(mut client: ResMut<LobbyClient>, time: Res<Time>) {
+    let Some(connection) = &mut client.connection else { return };
+    for response in connection.receive::<LobbyResponse>() {
+        match response {
+            LobbyResponse::Room(room) => {
+                client.room = Some(room);
+                client.error = None;
+            }
+            // answers to requests sent before the client found its room are out of date
+            LobbyResponse::NotFound if client.room.is_none() => {
+                client.error = Some(LobbyError::NotFound)
+            }
+            LobbyResponse::Full if client.room.is_none() => client.error = Some(LobbyError::Full),
+            LobbyResponse::NotFound | LobbyResponse::Full => {}
+        }
+    }
+
+    // requests can be lost, so they are sent again until the match starts,
+    // unless the lobby has already said no
+    if client.error.is_none() {
+        client.since_request += time.delta_seconds();
+        if client.since_request >= LobbyClient::REQUEST_INTERVAL {
+            client.send_request();
+        }
+    }
+}
+
+// Finds a room on the lobby while on the given screen, and learns where its match is played.
+// Moving on to the match is left to the app, once `LobbyClient::match_server` is known.
+pub struct LobbyClientPlugin<Screen: States> {
+    active_screen: Screen,
+}
+
+impl<Screen: States> LobbyClientPlugin<Screen> {
+    pub fn new(active_screen: Screen) -> Self {
+        LobbyClientPlugin { active_screen }
+    }
+}
+
+impl<Screen: States + Copy> Plugin for LobbyClientPlugin<Screen> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LobbyClient>()
+            .add_systems(OnEnter(self.active_screen), enter_lobby)
+            .add_systems(OnExit(self.active_screen), leave_lobby)
+            .add_systems(
+                Update,
+                update_lobby_client.run_if(in_state(self.active_screen)),
+            );
+    }
+}
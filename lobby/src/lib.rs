@@ -0,0 +1,10 @@
+mod client;
+pub use client::{LobbyAddress, LobbyClient, LobbyClientPlugin, LobbyError, RoomChoice};
+
+mod protocol;
+pub use protocol::{LobbyRequest, LobbyResponse, RoomCode, RoomState, Seat};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod server;
+#[cfg(not(target_arch = "wasm32"))]
+pub use server::{LobbyServer, LobbyServerPlugin};
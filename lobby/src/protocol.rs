@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+use cricket_pong_game::base::Identity;
+
+// A short code that one player passes on to another, so that they can find the same room.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RoomCode(pub(crate) String);
+
+impl RoomCode {
+    pub const LENGTH: usize = 4;
+    // letters that are easily mistaken for numbers are left out
+    pub(crate) const LETTERS: &'static [u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ";
+
+    // reads a code the way a player might type it in, in either case and with spaces around it
+    pub fn parse(text: &str) -> Option<Self> {
+        let code = text.trim().to_ascii_uppercase();
+        let valid = code.len() == Self::LENGTH
+            && code.bytes().all(|letter| Self::LETTERS.contains(&letter));
+        valid.then_some(RoomCode(code))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for RoomCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+// Sent from a client to the lobby.
+// Each is repeated until the lobby answers, and doubles as a sign that the client is still there.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum LobbyRequest {
+    // opens a new room, with the client in the first seat
+    Host,
+    Join(RoomCode),
+    // whether the client is ready for the match to start
+    Ready(bool),
+    Leave,
+}
+
+// One of the two places in a room.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Seat {
+    pub player: Identity,
+    pub ready: bool,
+}
+
+// Everything a client in a room needs to know about it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RoomState {
+    pub code: RoomCode,
+    pub seats: Vec<Seat>,
+    // the seat that belongs to the client this was sent to
+    pub you: Identity,
+    // what the client rejoins the match with to be given its seat, once everyone is ready
+    pub token: Option<u64>,
+    // where the match is played, once everyone is ready, in a form `ServerAddress` can parse
+    pub server: Option<String>,
+}
+
+// Sent from the lobby to a client.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum LobbyResponse {
+    Room(RoomState),
+    // there is no room with that code
+    NotFound,
+    // both seats in the room are taken
+    Full,
+}
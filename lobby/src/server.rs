@@ -0,0 +1,410 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::mpsc,
+    time::Duration,
+};
+
+use bevy::{
+    app::{AppExit, ScheduleRunnerPlugin},
+    prelude::{
+        App, EventWriter, IntoSystemConfigs, Local, Plugin, Res, ResMut, Resource, Time, Update,
+    },
+};
+
+use rand::Rng;
+
+use cricket_pong_game::base::Identity;
+use cricket_pong_net::{headless_app, ClientAddress, Server, ServerPlugin, ServerTransport};
+
+use crate::protocol::{LobbyRequest, LobbyResponse, RoomCode, RoomState, Seat};
+
+// A client sitting in a room.
+struct Member {
+    address: ClientAddress,
+    player: Identity,
+    ready: bool,
+    // when the lobby last heard from the client
+    last_heard: f32,
+    // handed out when the match starts, and the only way onto the game server
+    token: Option<u64>,
+}
+
+// A game server hosting one room's match.
+struct GameServer {
+    udp: SocketAddr,
+    websocket: SocketAddr,
+}
+
+impl GameServer {
+    // clients are sent to the game server over the same transport they reached the lobby with
+    fn address_for(&self, client: ClientAddress) -> String {
+        match client {
+            ClientAddress::Udp(_) => self.udp.to_string(),
+            ClientAddress::WebSocket(_) => format!("ws://{}", self.websocket),
+        }
+    }
+}
+
+struct Room {
+    code: RoomCode,
+    members: Vec<Member>,
+    server: Option<GameServer>,
+    // something has happened that the members should hear about straight away
+    changed: bool,
+}
+
+impl Room {
+    fn state_for(&self, member: &Member) -> RoomState {
+        RoomState {
+            code: self.code.clone(),
+            seats: self
+                .members
+                .iter()
+                .map(|member| Seat {
+                    player: member.player,
+                    ready: member.ready,
+                })
+                .collect(),
+            you: member.player,
+            token: member.token,
+            server: self
+                .server
+                .as_ref()
+                .map(|server| server.address_for(member.address)),
+        }
+    }
+
+    // gives the client whichever seat is still free, if any
+    fn seat(&mut self, address: ClientAddress, now: f32) -> Option<Identity> {
+        let player = [Identity::One, Identity::Two]
+            .into_iter()
+            .find(|player| self.members.iter().all(|member| member.player != *player))?;
+        self.members.push(Member {
+            address,
+            player,
+            ready: false,
+            last_heard: now,
+            token: None,
+        });
+        self.changed = true;
+        Some(player)
+    }
+
+    fn hear_from(&mut self, address: ClientAddress, now: f32) {
+        for member in self.members.iter_mut() {
+            if member.address == address {
+                member.last_heard = now;
+            }
+        }
+    }
+
+    fn set_ready(&mut self, address: ClientAddress, ready: bool) {
+        // the match has already been handed off, so nothing more can change
+        if self.server.is_some() {
+            return;
+        }
+        for member in self.members.iter_mut() {
+            if member.address == address && member.ready != ready {
+                member.ready = ready;
+                self.changed = true;
+            }
+        }
+    }
+}
+
+// The rooms on this lobby, and the transport their clients talk to it through.
+#[derive(Resource)]
+pub struct LobbyServer {
+    transport: ServerTransport,
+    rooms: Vec<Room>,
+    // where game servers are bound, and the host that clients are told to reach them at
+    game_host: IpAddr,
+    public_host: IpAddr,
+}
+
+impl LobbyServer {
+    // how long a client can go quiet before it loses its seat
+    const TIMEOUT: f32 = 5.;
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.transport
+            .udp_addr()
+            .expect("the lobby socket should be bound")
+    }
+
+    // where websocket clients can connect, if the lobby is listening for them
+    pub fn websocket_addr(&self) -> Option<SocketAddr> {
+        self.transport
+            .websocket_addr()
+            .map(|address| address.expect("the websocket listener should be bound"))
+    }
+
+    pub fn room_count(&self) -> usize {
+        self.rooms.len()
+    }
+
+    fn room_of(&mut self, address: ClientAddress) -> Option<&mut Room> {
+        self.rooms
+            .iter_mut()
+            .find(|room| room.members.iter().any(|member| member.address == address))
+    }
+
+    fn leave(&mut self, address: ClientAddress) {
+        for room in self.rooms.iter_mut() {
+            let before = room.members.len();
+            room.members.retain(|member| member.address != address);
+            room.changed |= room.members.len() != before;
+        }
+        self.rooms.retain(|room| !room.members.is_empty());
+    }
+
+    fn new_code(&self) -> RoomCode {
+        let mut rng = rand::thread_rng();
+        loop {
+            let code: String = (0..RoomCode::LENGTH)
+                .map(|_| RoomCode::LETTERS[rng.gen_range(0..RoomCode::LETTERS.len())] as char)
+                .collect();
+            let code = RoomCode(code);
+            if self.rooms.iter().all(|room| room.code != code) {
+                return code;
+            }
+        }
+    }
+}
+
+// Runs a game server for one match on a thread of its own, and says where it can be reached.
+// Only the players holding the tokens in `seats` are let in.
+fn launch_game_server(
+    host: IpAddr,
+    seats: Vec<(Identity, u64)>,
+) -> std::io::Result<(SocketAddr, SocketAddr)> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let any_port = SocketAddr::new(host, 0);
+        let mut app = headless_app();
+        app.add_plugins((
+            ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(1. / 60.)),
+            ServerPlugin::new(any_port)
+                .with_websocket(any_port)
+                .with_reserved_seats(seats),
+        ))
+        .add_systems(Update, close_when_finished);
+        let server = app.world.resource::<Server>();
+        let addresses = (server.local_addr(), server.websocket_addr());
+        if let (udp, Some(websocket)) = addresses {
+            let _ = sender.send((udp, websocket));
+            app.run();
+        }
+    });
+    // the thread only hangs up without an answer if the server couldn't be bound
+    receiver.recv().map_err(|_| {
+        std::io::Error::new(
+            std::io::ErrorKind::AddrNotAvailable,
+            "the game server couldn't be started",
+        )
+    })
+}
+
+// Players are given a while to arrive, and the game server closes once they have all left.
+fn close_when_finished(
+    server: Res<Server>,
+    time: Res<Time>,
+    mut has_joined: Local<bool>,
+    mut exit: EventWriter<AppExit>,
+) {
+    const ARRIVAL_TIME: f32 = 60.;
+    *has_joined |= server.client_count() > 0;
+    let finished = if *has_joined {
+        server.client_count() == 0
+    } else {
+        time.elapsed_seconds() > ARRIVAL_TIME
+    };
+    if finished {
+        exit.send(AppExit);
+    }
+}
+
+fn receive_requests(mut lobby: ResMut<LobbyServer>, time: Res<Time>) {
+    let now = time.elapsed_seconds();
+    let lobby = &mut *lobby;
+    for (request, address) in lobby.transport.receive::<LobbyRequest>() {
+        if let Some(room) = lobby.room_of(address) {
+            room.hear_from(address, now);
+        }
+        match request {
+            LobbyRequest::Host => match lobby.room_of(address) {
+                // asked again before hearing about the room it already has
+                Some(room) => room.changed = true,
+                None => {
+                    let mut room = Room {
+                        code: lobby.new_code(),
+                        members: Vec::new(),
+                        server: None,
+                        changed: true,
+                    };
+                    room.seat(address, now);
+                    lobby.rooms.push(room);
+                }
+            },
+            LobbyRequest::Join(code) => {
+                if let Some(room) = lobby.room_of(address) {
+                    if room.code == code {
+                        room.changed = true;
+                        continue;
+                    }
+                }
+                // moving to another room means leaving this one first
+                lobby.leave(address);
+                let Some(room) = lobby.rooms.iter_mut().find(|room| room.code == code) else {
+                    lobby.transport.send(&LobbyResponse::NotFound, address);
+                    continue;
+                };
+                // a room that has started its match can't take anyone new
+                if room.server.is_some() || room.seat(address, now).is_none() {
+                    lobby.transport.send(&LobbyResponse::Full, address);
+                }
+            }
+            LobbyRequest::Ready(ready) => {
+                if let Some(room) = lobby.room_of(address) {
+                    room.set_ready(address, ready);
+                }
+            }
+            LobbyRequest::Leave => lobby.leave(address),
+        }
+    }
+}
+
+// rooms where both seats are taken and ready are given a game server to play on
+fn start_matches(mut lobby: ResMut<LobbyServer>) {
+    let game_host = lobby.game_host;
+    let public_host = lobby.public_host;
+    for room in lobby.rooms.iter_mut() {
+        let all_ready = room.members.len() == 2 && room.members.iter().all(|member| member.ready);
+        if room.server.is_some() || !all_ready {
+            continue;
+        }
+        let mut rng = rand::thread_rng();
+        for member in room.members.iter_mut() {
+            member.token = Some(rng.gen());
+        }
+        let seats = room
+            .members
+            .iter()
+            .filter_map(|member| Some((member.player, member.token?)))
+            .collect();
+        match launch_game_server(game_host, seats) {
+            Ok((udp, websocket)) => {
+                room.server = Some(GameServer {
+                    udp: SocketAddr::new(public_host, udp.port()),
+                    websocket: SocketAddr::new(public_host, websocket.port()),
+                });
+            }
+            // everyone can ready up again to have another go
+            Err(_) => {
+                for member in room.members.iter_mut() {
+                    member.ready = false;
+                    member.token = None;
+                }
+            }
+        }
+        room.changed = true;
+    }
+}
+
+fn drop_silent_members(mut lobby: ResMut<LobbyServer>, time: Res<Time>) {
+    let now = time.elapsed_seconds();
+    let silent: Vec<ClientAddress> = lobby
+        .rooms
+        .iter()
+        .flat_map(|room| room.members.iter())
+        .filter(|member| now - member.last_heard > LobbyServer::TIMEOUT)
+        .map(|member| member.address)
+        .collect();
+    for address in silent {
+        lobby.leave(address);
+    }
+}
+
+// every member hears about their room as soon as it changes, and again every so often
+// in case that was lost
+fn send_room_states(mut lobby: ResMut<LobbyServer>, time: Res<Time>, mut since_sent: Local<f32>) {
+    const RESEND_INTERVAL: f32 = 0.5;
+    *since_sent += time.delta_seconds();
+    let resend = *since_sent >= RESEND_INTERVAL;
+    if resend {
+        *since_sent = 0.;
+    }
+    let LobbyServer {
+        transport, rooms, ..
+    } = &mut *lobby;
+    for room in rooms.iter_mut() {
+        if !room.changed && !resend {
+            continue;
+        }
+        room.changed = false;
+        for member in room.members.iter() {
+            transport.send(&LobbyResponse::Room(room.state_for(member)), member.address);
+        }
+    }
+}
+
+// Lets players open rooms, find each other's rooms by code, and ready up.
+// Once both players in a room are ready, a game server is started for their match,
+// and they are told where to find it.
+pub struct LobbyServerPlugin {
+    address: SocketAddr,
+    websocket_address: Option<SocketAddr>,
+    public_host: Option<IpAddr>,
+}
+
+impl LobbyServerPlugin {
+    pub fn new(address: SocketAddr) -> Self {
+        LobbyServerPlugin {
+            address,
+            websocket_address: None,
+            public_host: None,
+        }
+    }
+
+    // also lets browsers find rooms, over websockets at `address`
+    pub fn with_websocket(mut self, address: SocketAddr) -> Self {
+        self.websocket_address = Some(address);
+        self
+    }
+
+    // the host that clients are told to reach game servers at,
+    // which is needed when the lobby is bound to every interface
+    pub fn with_public_host(mut self, host: IpAddr) -> Self {
+        self.public_host = Some(host);
+        self
+    }
+}
+
+impl Plugin for LobbyServerPlugin {
+    fn build(&self, app: &mut App) {
+        let transport = ServerTransport::bind(self.address, self.websocket_address)
+            .expect("failed to bind the lobby socket");
+        let game_host = self.address.ip();
+        let public_host = self.public_host.unwrap_or(if game_host.is_unspecified() {
+            IpAddr::V4(Ipv4Addr::LOCALHOST)
+        } else {
+            game_host
+        });
+        app.insert_resource(LobbyServer {
+            transport,
+            rooms: Vec::new(),
+            game_host,
+            public_host,
+        })
+        .add_systems(
+            Update,
+            (
+                receive_requests,
+                drop_silent_members,
+                start_matches,
+                send_room_states,
+            )
+                .chain(),
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use std::{net::SocketAddr, time::Duration};
+
+use bevy::prelude::{App, States};
+
+use cricket_pong_lobby::{
+    LobbyAddress, LobbyClient, LobbyClientPlugin, LobbyError, LobbyServer, LobbyServerPlugin,
+    RoomChoice, RoomCode,
+};
+use cricket_pong_net::{headless_app, ClientPlugin, NetClient, ServerAddress, SessionToken};
+
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, States)]
+enum TestScreen {
+    #[default]
+    Lobby,
+}
+
+const FRAME: Duration = Duration::from_millis(5);
+const MAX_FRAMES: usize = 2000;
+
+fn lobby_app() -> App {
+    let localhost = SocketAddr::from(([127, 0, 0, 1], 0));
+    let mut app = headless_app();
+    app.add_plugins(LobbyServerPlugin::new(localhost).with_websocket(localhost));
+    app
+}
+
+fn client_app(lobby: ServerAddress, choice: RoomChoice) -> App {
+    let mut app = headless_app();
+    app.add_state::<TestScreen>()
+        .insert_resource(LobbyAddress(lobby))
+        .insert_resource(choice)
+        .add_plugins(LobbyClientPlugin::new(TestScreen::Lobby));
+    app
+}
+
+fn lobby_client(app: &App) -> &LobbyClient {
+    app.world.resource::<LobbyClient>()
+}
+
+// steps every app until `done` is true, failing if that takes too long
+fn run_until(apps: &mut [&mut App], mut done: impl FnMut(&mut [&mut App]) -> bool) {
+    for _ in 0..MAX_FRAMES {
+        for app in apps.iter_mut() {
+            app.update();
+        }
+        if done(apps) {
+            return;
+        }
+        std::thread::sleep(FRAME);
+    }
+    panic!("the apps never reached the expected state");
+}
+
+#[test]
+fn players_meet_by_code_and_are_handed_off_to_a_game_server() {
+    let mut lobby = lobby_app();
+    let server = lobby.world.resource::<LobbyServer>();
+    let udp_address = ServerAddress::Udp(server.local_addr());
+    let websocket_address = ServerAddress::WebSocket(format!(
+        "ws://{}",
+        server.websocket_addr().expect("websockets were asked for")
+    ));
+    let mut host = client_app(udp_address, RoomChoice::Host);
+
+    run_until(&mut [&mut lobby, &mut host], |apps| {
+        lobby_client(apps[1]).room().is_some()
+    });
+    let code = lobby_client(&host).room().unwrap().code.clone();
+
+    // the second player joins over websockets, and still finds the same room
+    let mut guest = client_app(websocket_address, RoomChoice::Join(code.clone()));
+    run_until(&mut [&mut lobby, &mut host, &mut guest], |apps| {
+        apps[1..].iter().all(|app| {
+            lobby_client(app)
+                .room()
+                .is_some_and(|room| room.seats.len() == 2)
+        })
+    });
+    let host_room = lobby_client(&host).room().unwrap();
+    let guest_room = lobby_client(&guest).room().unwrap();
+    assert_eq!(guest_room.code, code);
+    assert_ne!(host_room.you, guest_room.you);
+    assert_eq!(lobby.world.resource::<LobbyServer>().room_count(), 1);
+
+    // no game server is started until both players are ready
+    host.world.resource_mut::<LobbyClient>().set_ready(true);
+    run_until(&mut [&mut lobby, &mut host, &mut guest], |apps| {
+        lobby_client(apps[2])
+            .room()
+            .is_some_and(|room| room.seats.iter().any(|seat| seat.ready))
+    });
+    assert!(lobby_client(&host).match_server().is_none());
+
+    guest.world.resource_mut::<LobbyClient>().set_ready(true);
+    run_until(&mut [&mut lobby, &mut host, &mut guest], |apps| {
+        lobby_client(apps[1]).match_server().is_some()
+            && lobby_client(apps[2]).match_server().is_some()
+    });
+    let host_server = lobby_client(&host).match_server().unwrap();
+    let guest_server = lobby_client(&guest).match_server().unwrap();
+    assert!(matches!(host_server, ServerAddress::Udp(_)));
+    assert!(matches!(guest_server, ServerAddress::WebSocket(_)));
+    let host_token = lobby_client(&host).match_token().unwrap();
+    let guest_token = lobby_client(&guest).match_token().unwrap();
+    assert_ne!(host_token, guest_token);
+
+    let game_client = |server: ServerAddress, token: Option<SessionToken>| {
+        let mut app = headless_app();
+        app.add_state::<TestScreen>()
+            .insert_resource(server)
+            .add_plugins(ClientPlugin::new(TestScreen::Lobby));
+        if let Some(token) = token {
+            app.insert_resource(token);
+        }
+        app
+    };
+    // anyone else who finds the game server is turned away, even before the players arrive
+    let mut stranger = game_client(host_server.clone(), None);
+    run_until(&mut [&mut stranger], |apps| {
+        apps[0].world.resource::<NetClient>().is_rejected()
+    });
+
+    // both players are given the seats they had in the room
+    let mut player_one = game_client(host_server, Some(host_token));
+    let mut player_two = game_client(guest_server, Some(guest_token));
+    run_until(&mut [&mut player_one, &mut player_two], |apps| {
+        apps.iter()
+            .all(|app| app.world.resource::<NetClient>().player().is_some())
+    });
+    assert_eq!(
+        player_one.world.resource::<NetClient>().player(),
+        Some(lobby_client(&host).room().unwrap().you)
+    );
+    assert_eq!(
+        player_two.world.resource::<NetClient>().player(),
+        Some(lobby_client(&guest).room().unwrap().you)
+    );
+}
+
+#[test]
+fn unknown_codes_and_full_rooms_are_refused() {
+    let mut lobby = lobby_app();
+    let address = ServerAddress::Udp(lobby.world.resource::<LobbyServer>().local_addr());
+    let unknown = RoomCode::parse("zzzz").expect("the code should be valid");
+    let mut lost = client_app(address.clone(), RoomChoice::Join(unknown));
+    let mut host = client_app(address.clone(), RoomChoice::Host);
+
+    run_until(&mut [&mut lobby, &mut lost, &mut host], |apps| {
+        lobby_client(apps[1]).error().is_some() && lobby_client(apps[2]).room().is_some()
+    });
+    assert_eq!(lobby_client(&lost).error(), Some(LobbyError::NotFound));
+    assert!(lobby_client(&lost).room().is_none());
+
+    let code = lobby_client(&host).room().unwrap().code.clone();
+    let mut guest = client_app(address.clone(), RoomChoice::Join(code.clone()));
+    let mut late = client_app(address, RoomChoice::Join(code));
+    run_until(&mut [&mut lobby, &mut host, &mut guest], |apps| {
+        lobby_client(apps[2]).room().is_some()
+    });
+    run_until(
+        &mut [&mut lobby, &mut host, &mut guest, &mut late],
+        |apps| lobby_client(apps[3]).error().is_some(),
+    );
+    assert_eq!(lobby_client(&late).error(), Some(LobbyError::Full));
+}
@@ -0,0 +1,76 @@
+use std::{cell::RefCell, io::ErrorKind, rc::Rc};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{BinaryType, MessageEvent, WebSocket};
+
+// A browser websocket that sends and receives whole messages, in the same format
+// as NetSocket does over UDP.
+pub struct WsConnection {
+    socket: WebSocket,
+    // messages are handed over by the browser between frames, and wait here to be read
+    received: Rc<RefCell<Vec<Vec<u8>>>>,
+    // kept alive for as long as the browser might call it
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+}
+
+// SAFETY: without the atomics feature, wasm can't spawn threads, so the `Rc` and the
+// browser handles are only ever touched from the one thread that made them.
+// A threaded build leaves these out, and fails to build until the connection is kept
+// somewhere that doesn't have to be sent between threads.
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+unsafe impl Send for WsConnection {}
+// SAFETY: as above, there is no other thread to share the connection with.
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+unsafe impl Sync for WsConnection {}
+
+impl WsConnection {
+    // starts connecting to a url like ws://127.0.0.1:7778
+    pub(crate) fn connect(url: &str) -> std::io::Result<Self> {
+        let socket = WebSocket::new(url).map_err(|_| {
+            std::io::Error::new(ErrorKind::InvalidInput, "the browser refused the websocket")
+        })?;
+        socket.set_binary_type(BinaryType::Arraybuffer);
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let inbox = received.clone();
+        let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            if let Ok(buffer) = event.data().dyn_into::<js_sys::ArrayBuffer>() {
+                inbox
+                    .borrow_mut()
+                    .push(js_sys::Uint8Array::new(&buffer).to_vec());
+            }
+        });
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        Ok(WsConnection {
+            socket,
+            received,
+            _on_message: on_message,
+        })
+    }
+
+    // anything sent before the connection is open is lost, which the protocol already allows for
+    pub(crate) fn send<T: Serialize>(&mut self, message: &T) {
+        if self.socket.ready_state() != WebSocket::OPEN {
+            return;
+        }
+        let Ok(bytes) = bincode::serialize(message) else { return };
+        let _ = self.socket.send_with_u8_array(&bytes);
+    }
+
+    // every message that has arrived since the last call, skipping any that can't be read
+    pub(crate) fn receive<T: DeserializeOwned>(&mut self) -> Vec<T> {
+        self.received
+            .borrow_mut()
+            .drain(..)
+            .filter_map(|bytes| bincode::deserialize(&bytes).ok())
+            .collect()
+    }
+}
+
+impl Drop for WsConnection {
+    fn drop(&mut self) {
+        self.socket.set_onmessage(None);
+        let _ = self.socket.close();
+    }
+}
@@ -0,0 +1,611 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    net::SocketAddr,
+};
+
+use bevy::prelude::{
+    in_state, App, Commands, DespawnRecursiveExt, Entity, IntoSystemConfigs, NextState, OnEnter,
+    OnExit, Plugin, PreUpdate, Query, Res, ResMut, Resource, SpatialBundle, State, States, Time,
+    Transform, Update, With,
+};
+
+use cricket_pong_game::{
+    actions::Actions,
+    base::{
+        ball::Ball,
+        batter::{Bat, Batter, Wicket},
+        fielder::{Boundary, Fielder, FielderRing},
+        Identity, PlayerOne, PlayerTwo, Position, Remote, Score,
+    },
+    Forfeit, GamePhase, GameplayMarkerPlugin, MatchPause, Over,
+};
+
+use crate::{
+    protocol::{BodyState, ClientMessage, ServerMessage, Snapshot},
+    transport::Connection,
+};
+
+// The server to play on, which must be set before the online screen is entered.
+// Browsers can't use UDP, so can only reach a server over websockets.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub enum ServerAddress {
+    Udp(SocketAddr),
+    // a url like ws://127.0.0.1:7778
+    WebSocket(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ServerAddressError {
+    Invalid(std::net::AddrParseError),
+    // wss:// urls need TLS, which only the browser build has
+    Unsupported,
+}
+
+impl std::str::FromStr for ServerAddress {
+    type Err = ServerAddressError;
+
+    // urls starting with ws:// or wss:// are reached over websockets, anything else over UDP
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        if address.starts_with("wss://") && cfg!(not(target_arch = "wasm32")) {
+            Err(ServerAddressError::Unsupported)
+        } else if address.starts_with("ws://") || address.starts_with("wss://") {
+            Ok(ServerAddress::WebSocket(address.to_string()))
+        } else {
+            address
+                .parse()
+                .map(ServerAddress::Udp)
+                .map_err(ServerAddressError::Invalid)
+        }
+    }
+}
+
+// Whether this client plays in the match or only watches it, which can be set before the
+// online screen is entered. Clients play unless told otherwise.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ClientRole {
+    #[default]
+    Player,
+    Spectator,
+}
+
+impl ClientRole {
+    fn join_message(self) -> ClientMessage {
+        match self {
+            ClientRole::Player => ClientMessage::Join,
+            ClientRole::Spectator => ClientMessage::Spectate,
+        }
+    }
+}
+
+// Proves which player a client was, so that it can take their place back after being cut off.
+// If this is set before the online screen is entered, the client rejoins instead of joining.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SessionToken(pub u64);
+
+// This client's connection to the server.
+#[derive(Resource, Default)]
+pub struct NetClient {
+    connection: Option<Connection>,
+    // where the server is, for opening the connection again if it drops
+    address: Option<ServerAddress>,
+    role: ClientRole,
+    player: Option<Identity>,
+    token: Option<SessionToken>,
+    // the server is letting this client watch
+    spectating: bool,
+    // the server turned this client away
+    rejected: bool,
+    // the server has answered since the connection was last opened
+    connected: bool,
+    // time since the last request to join
+    since_join: f32,
+    // time since the last message from the server
+    since_heard: f32,
+    // time since the server was last told that this client is still here
+    since_keep_alive: f32,
+}
+
+impl NetClient {
+    // how long to wait for an answer before asking to join again
+    const JOIN_INTERVAL: f32 = 0.5;
+    const KEEP_ALIVE_INTERVAL: f32 = 0.5;
+    // how long the server can go quiet during a match before the connection is opened again
+    const TIMEOUT: f32 = 2.;
+
+    // the player this client controls, once the server has let it join
+    pub fn player(&self) -> Option<Identity> {
+        self.player
+    }
+
+    pub fn is_rejected(&self) -> bool {
+        self.rejected
+    }
+
+    pub fn is_spectating(&self) -> bool {
+        self.spectating
+    }
+
+    // whether the server has answered, either with a place in the match or a seat to watch from
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    // keeping this lets the player rejoin the match, even from a fresh start
+    pub fn session_token(&self) -> Option<SessionToken> {
+        self.token
+    }
+
+    // a client that has had a place in the match asks for that place back
+    fn join_message(&self) -> ClientMessage {
+        match self.token {
+            Some(SessionToken(token)) => ClientMessage::Rejoin { token },
+            None => self.role.join_message(),
+        }
+    }
+}
+
+// Snapshots waiting to be shown.
+#[derive(Resource, Default)]
+struct SnapshotBuffer {
+    snapshots: VecDeque<Snapshot>,
+    // the smallest gap seen between the server's clock and this one,
+    // which is taken from the snapshot that arrived the quickest
+    clock_offset: Option<f32>,
+}
+
+impl SnapshotBuffer {
+    // snapshots are shown this long after they were taken, so that there is almost
+    // always a newer one to move towards
+    const INTERPOLATION_DELAY: f32 = 0.1;
+    const CAPACITY: usize = 32;
+
+    fn push(&mut self, snapshot: Snapshot, now: f32) {
+        // snapshots can arrive out of order, and late ones are no use
+        if self
+            .snapshots
+            .back()
+            .is_some_and(|latest| latest.time >= snapshot.time)
+        {
+            return;
+        }
+        let offset = now - snapshot.time;
+        self.clock_offset = Some(self.clock_offset.map_or(offset, |old| old.min(offset)));
+        self.snapshots.push_back(snapshot);
+        if self.snapshots.len() > Self::CAPACITY {
+            self.snapshots.pop_front();
+        }
+    }
+
+    fn latest(&self) -> Option<&Snapshot> {
+        self.snapshots.back()
+    }
+
+    // the snapshots either side of what should be shown now, and how far between them that is
+    fn bracket(&mut self, now: f32) -> Option<(&Snapshot, &Snapshot, f32)> {
+        let shown_time = now - self.clock_offset? - Self::INTERPOLATION_DELAY;
+        // anything before the earlier of the two has already been shown
+        while self
+            .snapshots
+            .get(1)
+            .is_some_and(|next| next.time <= shown_time)
+        {
+            self.snapshots.pop_front();
+        }
+        let from = self.snapshots.front()?;
+        let Some(to) = self.snapshots.get(1) else {
+            return Some((from, from, 0.));
+        };
+        let t = ((shown_time - from.time) / (to.time - from.time)).clamp(0., 1.);
+        Some((from, to, t))
+    }
+
+    fn clear(&mut self) {
+        *self = SnapshotBuffer::default();
+    }
+}
+
+// Marks an entity that copies one of the server's moving bodies.
+#[derive(bevy::prelude::Component)]
+pub struct Replica(pub u64);
+
+// The entities standing in for the server's.
+#[derive(Resource, Default)]
+struct Replicas {
+    bodies: HashMap<u64, Entity>,
+    // player one, then player two
+    players: [Option<Entity>; 2],
+    // parts of the ground that never move
+    scenery: Vec<Entity>,
+}
+
+// should be run OnEnter(active_screen)
+fn connect(
+    mut commands: Commands,
+    mut client: ResMut<NetClient>,
+    mut replicas: ResMut<Replicas>,
+    address: Option<Res<ServerAddress>>,
+    role: Option<Res<ClientRole>>,
+    token: Option<Res<SessionToken>>,
+) {
+    let Some(address) = address else { return };
+    let Ok(connection) = Connection::open(&address) else { return };
+    *client = NetClient {
+        connection: Some(connection),
+        address: Some(address.clone()),
+        role: role.map_or(ClientRole::default(), |role| *role),
+        token: token.map(|token| *token),
+        ..Default::default()
+    };
+    let message = client.join_message();
+    if let Some(connection) = &mut client.connection {
+        connection.send(&message);
+    }
+    replicas.scenery = vec![
+        commands.spawn(FielderRing::Infield).id(),
+        commands.spawn(FielderRing::Outfield).id(),
+        commands.spawn((Boundary, SpatialBundle::default())).id(),
+        commands
+            .spawn((Wicket::default(), SpatialBundle::default()))
+            .id(),
+    ];
+}
+
+// should be run OnExit(active_screen)
+#[allow(clippy::too_many_arguments)]
+fn disconnect(
+    mut commands: Commands,
+    mut client: ResMut<NetClient>,
+    mut replicas: ResMut<Replicas>,
+    mut buffer: ResMut<SnapshotBuffer>,
+    mut over: ResMut<Over>,
+    mut phase: ResMut<NextState<GamePhase>>,
+    mut pause: ResMut<NextState<MatchPause>>,
+    mut forfeit: ResMut<Forfeit>,
+) {
+    if let Some(connection) = &mut client.connection {
+        connection.send(&ClientMessage::Leave);
+    }
+    *client = NetClient::default();
+    let Replicas {
+        bodies,
+        players,
+        scenery,
+    } = std::mem::take(&mut *replicas);
+    for entity in bodies
+        .into_values()
+        .chain(players.into_iter().flatten())
+        .chain(scenery)
+    {
+        commands.entity(entity).despawn_recursive();
+    }
+    buffer.clear();
+    over.clear();
+    phase.set(GamePhase::Inactive);
+    pause.set(MatchPause::Running);
+    *forfeit = Forfeit::default();
+}
+
+fn receive_server_messages(
+    mut client: ResMut<NetClient>,
+    mut buffer: ResMut<SnapshotBuffer>,
+    time: Res<Time>,
+) {
+    let Some(connection) = &mut client.connection else { return };
+    let now = time.elapsed_seconds();
+    let messages = connection.receive::<ServerMessage>();
+    if messages.is_empty() {
+        client.since_heard += time.delta_seconds();
+    } else {
+        client.since_heard = 0.;
+    }
+    for message in messages {
+        match message {
+            ServerMessage::Welcome { player, token } => {
+                client.player = Some(player);
+                client.token = Some(SessionToken(token));
+                client.connected = true;
+            }
+            ServerMessage::Spectating => {
+                client.spectating = true;
+                client.connected = true;
+            }
+            ServerMessage::Full => client.rejected = true,
+            ServerMessage::Snapshot(snapshot) => buffer.push(snapshot, now),
+        }
+    }
+
+    // Snapshots stop arriving if the link to the server breaks, so the connection is opened
+    // again and the client asks for its place back. Whatever was missed is made up for by
+    // the next snapshot, which holds the whole match.
+    if client.connected && buffer.latest().is_some() && client.since_heard > NetClient::TIMEOUT {
+        let reopened = client.address.as_ref().map(Connection::open);
+        if let Some(Ok(connection)) = reopened {
+            client.connection = Some(connection);
+        }
+        client.connected = false;
+        client.since_join = NetClient::JOIN_INTERVAL;
+        buffer.clear();
+    }
+
+    if client.connected {
+        client.since_keep_alive += time.delta_seconds();
+        if client.since_keep_alive >= NetClient::KEEP_ALIVE_INTERVAL {
+            client.since_keep_alive = 0.;
+            if let Some(connection) = &mut client.connection {
+                connection.send(&ClientMessage::KeepAlive);
+            }
+        }
+    }
+
+    // requests to join can be lost, so keep asking until the server answers
+    if !client.connected && !client.rejected {
+        client.since_join += time.delta_seconds();
+        if client.since_join >= NetClient::JOIN_INTERVAL {
+            client.since_join = 0.;
+            let message = client.join_message();
+            if let Some(connection) = &mut client.connection {
+                connection.send(&message);
+            }
+        }
+    }
+}
+
+// the match is played on the server, so actions are sent there instead of being consumed,
+// and spectators have no player to send them for
+fn send_actions(mut client: ResMut<NetClient>, mut actions: ResMut<Actions>) {
+    let queued = actions.0.drain(..);
+    let player = client.player;
+    let (Some(connection), Some(player)) = (&mut client.connection, player) else { return };
+    let own_actions = queued
+        .filter(|queued| queued.player == player)
+        .map(|queued| queued.action)
+        .collect::<Vec<_>>();
+    if !own_actions.is_empty() {
+        connection.send(&ClientMessage::Actions(own_actions));
+    }
+}
+
+// scores, positions, the over and the game phase are shown as soon as they arrive
+#[allow(clippy::too_many_arguments)]
+fn apply_match_state(
+    mut commands: Commands,
+    client: Res<NetClient>,
+    buffer: Res<SnapshotBuffer>,
+    mut replicas: ResMut<Replicas>,
+    mut player_query: Query<(&mut Position, &mut Score)>,
+    mut over: ResMut<Over>,
+    phase: Res<State<GamePhase>>,
+    mut next_phase: ResMut<NextState<GamePhase>>,
+) {
+    let Some(snapshot) = buffer.latest() else { return };
+    for state in snapshot.players.iter() {
+        let index = match state.player {
+            Identity::One => 0,
+            Identity::Two => 1,
+        };
+        match replicas.players[index].and_then(|entity| player_query.get_mut(entity).ok()) {
+            Some((mut position, mut score)) => {
+                if *position != state.position {
+                    *position = state.position;
+                }
+                if score.0 != state.score {
+                    score.0 = state.score;
+                }
+            }
+            None if replicas.players[index].is_none() => {
+                let mut builder = commands.spawn((state.position, Score(state.score)));
+                match state.player {
+                    Identity::One => builder.insert(PlayerOne),
+                    Identity::Two => builder.insert(PlayerTwo),
+                };
+                if client.player != Some(state.player) {
+                    builder.insert(Remote);
+                }
+                replicas.players[index] = Some(builder.id());
+            }
+            // spawned this frame, and not ready to be updated yet
+            None => {}
+        }
+    }
+    // a client that has been away may have the right number of balls with the wrong scores
+    if *over != snapshot.over {
+        *over = snapshot.over.clone();
+    }
+    if *phase.get() != snapshot.phase {
+        next_phase.set(snapshot.phase);
+    }
+}
+
+// the match is held still on the server while a player is missing, and shown held still here
+fn apply_interruptions(
+    buffer: Res<SnapshotBuffer>,
+    pause: Res<State<MatchPause>>,
+    mut next_pause: ResMut<NextState<MatchPause>>,
+    mut forfeit: ResMut<Forfeit>,
+) {
+    let Some(snapshot) = buffer.latest() else { return };
+    let shown = if snapshot.paused {
+        MatchPause::Paused
+    } else {
+        MatchPause::Running
+    };
+    if *pause.get() != shown {
+        next_pause.set(shown);
+    }
+    if forfeit.0 != snapshot.forfeit {
+        forfeit.0 = snapshot.forfeit;
+    }
+}
+
+// moves the replica of `body` to where it was, spawning it first if this is its first sighting
+fn place_replica(
+    replicas: &mut Replicas,
+    transform_query: &mut Query<&mut Transform, With<Replica>>,
+    body: BodyState,
+    spawn: impl FnOnce(Transform) -> Entity,
+) {
+    match replicas.bodies.get(&body.id) {
+        Some(entity) => {
+            if let Ok(mut transform) = transform_query.get_mut(*entity) {
+                body.apply(&mut transform);
+            }
+        }
+        None => {
+            let mut transform = Transform::from_xyz(0., 0., 1.);
+            body.apply(&mut transform);
+            replicas.bodies.insert(body.id, spawn(transform));
+        }
+    }
+}
+
+// moves every replica to where its body was, between the two snapshots either side of now
+fn sync_replicas(
+    mut commands: Commands,
+    mut buffer: ResMut<SnapshotBuffer>,
+    mut replicas: ResMut<Replicas>,
+    mut transform_query: Query<&mut Transform, With<Replica>>,
+    mut bat_query: Query<&mut Bat, With<Replica>>,
+    time: Res<Time>,
+) {
+    let Some((from, to, t)) = buffer.bracket(time.elapsed_seconds()) else { return };
+    let shown = |body: &BodyState| match from.bodies().find(|earlier| earlier.id == body.id) {
+        Some(earlier) => earlier.lerp(body, t),
+        None => *body,
+    };
+
+    let replicas = &mut *replicas;
+    for ball in to.balls.iter() {
+        place_replica(
+            replicas,
+            &mut transform_query,
+            shown(&ball.body),
+            |transform| {
+                commands
+                    .spawn((
+                        Replica(ball.body.id),
+                        Ball::new(ball.kind),
+                        SpatialBundle::from_transform(transform),
+                    ))
+                    .id()
+            },
+        );
+    }
+    if let Some(bat) = &to.bat {
+        place_replica(
+            replicas,
+            &mut transform_query,
+            shown(&bat.body),
+            |transform| {
+                commands
+                    .spawn((
+                        Replica(bat.body.id),
+                        Batter::default(),
+                        bat.bat,
+                        SpatialBundle::from_transform(transform),
+                    ))
+                    .id()
+            },
+        );
+        // the bat changes hands when the players swap, and with power-ups
+        let replica = replicas.bodies.get(&bat.body.id);
+        if let Some(mut shown_bat) = replica.and_then(|entity| bat_query.get_mut(*entity).ok()) {
+            if *shown_bat != bat.bat {
+                *shown_bat = bat.bat;
+            }
+        }
+    }
+    for fielder in to.fielders.iter() {
+        place_replica(
+            replicas,
+            &mut transform_query,
+            shown(&fielder.body),
+            |transform| {
+                commands
+                    .spawn((
+                        Replica(fielder.body.id),
+                        Fielder::new(fielder.position, fielder.ring),
+                        SpatialBundle::from_transform(transform),
+                    ))
+                    .id()
+            },
+        );
+    }
+
+    // bodies that are gone from the server are gone here too
+    replicas.bodies.retain(|id, entity| {
+        let keep = to.bodies().any(|body| body.id == *id);
+        if !keep {
+            commands.entity(*entity).despawn_recursive();
+        }
+        keep
+    });
+}
+
+// Plays a match hosted on a server, on the given screen, or only watches it as a spectator.
+// Local actions are sent to the server, and the match is shown from the snapshots it sends back.
+pub struct ClientPlugin<Screen: States> {
+    active_screen: Screen,
+}
+
+impl<Screen: States> ClientPlugin<Screen> {
+    pub fn new(active_screen: Screen) -> Self {
+        ClientPlugin { active_screen }
+    }
+}
+
+impl<Screen: States + Copy> Plugin for ClientPlugin<Screen> {
+    fn build(&self, app: &mut App) {
+        // a client doesn't need to play the match itself, but it does keep track of it
+        if !GameplayMarkerPlugin::is_added(app) {
+            app.add_state::<GamePhase>()
+                .add_state::<MatchPause>()
+                .init_resource::<Over>()
+                .init_resource::<Forfeit>();
+        }
+        app.init_resource::<Actions>()
+            .init_resource::<NetClient>()
+            .init_resource::<SnapshotBuffer>()
+            .init_resource::<Replicas>()
+            .add_systems(OnEnter(self.active_screen), connect)
+            .add_systems(OnExit(self.active_screen), disconnect)
+            .add_systems(
+                PreUpdate,
+                receive_server_messages.run_if(in_state(self.active_screen)),
+            )
+            .add_systems(
+                Update,
+                (
+                    send_actions,
+                    apply_match_state,
+                    apply_interruptions,
+                    sync_replicas,
+                )
+                    .run_if(in_state(self.active_screen)),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_addresses_choose_their_transport() {
+        assert_eq!(
+            "127.0.0.1:7777".parse(),
+            Ok(ServerAddress::Udp(SocketAddr::from(([127, 0, 0, 1], 7777))))
+        );
+        assert_eq!(
+            "ws://127.0.0.1:7778".parse(),
+            Ok(ServerAddress::WebSocket("ws://127.0.0.1:7778".to_string()))
+        );
+        assert_eq!(
+            "wss://example.com".parse::<ServerAddress>(),
+            Err(ServerAddressError::Unsupported)
+        );
+        assert!(matches!(
+            "example.com".parse::<ServerAddress>(),
+            Err(ServerAddressError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,27 @@
+pub use cricket_pong_support::{headless_app, NetSocket};
+
+mod client;
+pub use client::{
+    ClientPlugin, ClientRole, NetClient, Replica, ServerAddress, ServerAddressError, SessionToken,
+};
+
+mod protocol;
+pub use protocol::{
+    BallState, BodyState, ClientMessage, FielderState, PlayerState, ServerMessage, Snapshot,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod server;
+#[cfg(not(target_arch = "wasm32"))]
+pub use server::{Server, ServerPlugin, ServerScreen, ServerSet};
+
+mod transport;
+pub use transport::Connection;
+#[cfg(not(target_arch = "wasm32"))]
+pub use transport::{ClientAddress, ServerTransport};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod websocket;
+
+#[cfg(target_arch = "wasm32")]
+mod browser;
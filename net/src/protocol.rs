@@ -0,0 +1,143 @@
+use bevy::prelude::{EulerRot, Quat, Transform, Vec2};
+
+use serde::{Deserialize, Serialize};
+
+use cricket_pong_game::{
+    actions::Action,
+    base::{
+        ball::BallKind,
+        batter::Bat,
+        fielder::{FielderPosition, FielderRing},
+        Identity, Over, Position,
+    },
+    GamePhase,
+};
+
+// Sent from a client to the server.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ClientMessage {
+    // asks for a place in the match, and is repeated until the server answers
+    Join,
+    // asks for the place in the match that `token` was handed out with, after being cut off,
+    // and is repeated until the server answers
+    Rejoin { token: u64 },
+    // asks to watch the match without playing in it, and is repeated until the server answers
+    Spectate,
+    // sent every so often, so that the server can tell the client is still there
+    KeepAlive,
+    // everything the player asked to do since the last message
+    Actions(Vec<Action>),
+    Leave,
+}
+
+// Sent from the server to a client.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum ServerMessage {
+    // the client has a place in the match, and will play as `player`
+    // `token` gets the place back if the client is cut off
+    Welcome { player: Identity, token: u64 },
+    // the client is watching the match, and will be sent snapshots but can't act
+    Spectating,
+    // both places in the match are taken
+    Full,
+    Snapshot(Snapshot),
+}
+
+// Where a moving body is, and how it is moving.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct BodyState {
+    // the body's entity on the server, which stays the same from one snapshot to the next
+    pub id: u64,
+    pub translation: Vec2,
+    // counter-clockwise, in radians
+    pub rotation: f32,
+    pub linvel: Vec2,
+    pub angvel: f32,
+}
+
+impl BodyState {
+    pub fn new(id: u64, transform: &Transform, linvel: Vec2, angvel: f32) -> Self {
+        BodyState {
+            id,
+            translation: transform.translation.truncate(),
+            rotation: transform.rotation.to_euler(EulerRot::ZYX).0,
+            linvel,
+            angvel,
+        }
+    }
+
+    // the state a fraction `t` of the way from this one to `other`
+    pub fn lerp(&self, other: &BodyState, t: f32) -> BodyState {
+        let turn = (other.rotation - self.rotation + std::f32::consts::PI)
+            .rem_euclid(std::f32::consts::TAU)
+            - std::f32::consts::PI;
+        BodyState {
+            id: other.id,
+            translation: self.translation.lerp(other.translation, t),
+            rotation: self.rotation + turn * t,
+            linvel: self.linvel.lerp(other.linvel, t),
+            angvel: self.angvel + (other.angvel - self.angvel) * t,
+        }
+    }
+
+    // keeps the depth of `transform`, which only matters for drawing
+    pub fn apply(&self, transform: &mut Transform) {
+        transform.translation.x = self.translation.x;
+        transform.translation.y = self.translation.y;
+        transform.rotation = Quat::from_rotation_z(self.rotation);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct BallState {
+    pub kind: BallKind,
+    pub body: BodyState,
+}
+
+// the bat is sent whole, since a power-up can change it from the one that was picked
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct BatState {
+    pub bat: Bat,
+    pub body: BodyState,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct FielderState {
+    pub position: FielderPosition,
+    pub ring: FielderRing,
+    pub body: BodyState,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct PlayerState {
+    pub player: Identity,
+    pub position: Position,
+    pub score: u16,
+}
+
+// Everything a client needs to show the match as it was on the server at `time`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    // seconds since the server started
+    pub time: f32,
+    pub phase: GamePhase,
+    pub players: Vec<PlayerState>,
+    pub balls: Vec<BallState>,
+    pub bat: Option<BatState>,
+    pub fielders: Vec<FielderState>,
+    pub over: Over,
+    // the match is held still while a player is missing
+    pub paused: bool,
+    pub forfeit: Option<Identity>,
+}
+
+impl Snapshot {
+    // every moving body in the snapshot
+    pub fn bodies(&self) -> impl Iterator<Item = &BodyState> {
+        self.balls
+            .iter()
+            .map(|ball| &ball.body)
+            .chain(self.bat.iter().map(|bat| &bat.body))
+            .chain(self.fielders.iter().map(|fielder| &fielder.body))
+    }
+}
@@ -0,0 +1,471 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    net::SocketAddr,
+};
+
+use bevy::{
+    ecs::query::Has,
+    prelude::{
+        in_state, App, Commands, Entity, GlobalTransform, IntoSystemConfigs, IntoSystemSetConfig,
+        NextState, OnEnter, Plugin, PostUpdate, PreUpdate, Query, Res, ResMut, Resource, State,
+        States, SystemSet, Time, Transform, Update, With,
+    },
+};
+
+use bevy_rapier2d::prelude::Velocity;
+
+use cricket_pong_game::{
+    actions::Actions,
+    base::{
+        ball::Ball,
+        batter::{Bat, Batter},
+        fielder::Fielder,
+        Identity, PlayerOne, PlayerTwo, Position, Score,
+    },
+    Forfeit, GamePhase, GameplayPlugin, MatchPause, Over,
+};
+
+use crate::{
+    protocol::{
+        BallState, BatState, BodyState, ClientMessage, FielderState, PlayerState, ServerMessage,
+        Snapshot,
+    },
+    transport::{ClientAddress, ServerTransport},
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, States)]
+pub enum ServerScreen {
+    // waiting for two players to join
+    #[default]
+    Lobby,
+    Match,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, SystemSet)]
+pub struct ServerSet;
+
+// A player's place in the match, which is held for them for a while if they go missing.
+struct Seat {
+    address: ClientAddress,
+    player: Identity,
+    // handed to the client when it joins, so that it can prove who it is if it comes back
+    token: u64,
+    // when the server last heard from the client
+    last_heard: f32,
+}
+
+// A client watching the match, who is sent snapshots but has no say in it.
+struct Spectator {
+    address: ClientAddress,
+    // when the server last heard from the client
+    last_heard: f32,
+}
+
+// The clients playing on this server, and the transport they play through.
+#[derive(Resource)]
+pub struct Server {
+    transport: ServerTransport,
+    seats: Vec<Seat>,
+    spectators: Vec<Spectator>,
+    // places kept for players who were handed a token elsewhere, such as by a lobby,
+    // who are the only ones let in when there are any
+    reserved: Vec<(Identity, u64)>,
+    // how long a missing player has to come back before they forfeit the match
+    grace_period: f32,
+}
+
+impl Server {
+    // how long a player can go quiet before they are counted as missing
+    const TIMEOUT: f32 = 2.;
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.transport
+            .udp_addr()
+            .expect("the server socket should be bound")
+    }
+
+    // where websocket clients can connect, if the server is listening for them
+    pub fn websocket_addr(&self) -> Option<SocketAddr> {
+        self.transport
+            .websocket_addr()
+            .map(|address| address.expect("the websocket listener should be bound"))
+    }
+
+    // players with a place in the match, including any that are missing
+    pub fn client_count(&self) -> usize {
+        self.seats.len()
+    }
+
+    pub fn spectator_count(&self) -> usize {
+        self.spectators.len()
+    }
+
+    // players who have gone quiet, and are being waited for
+    fn missing_players(&self, now: f32) -> Vec<Identity> {
+        self.seats
+            .iter()
+            .filter(|seat| now - seat.last_heard > Self::TIMEOUT)
+            .map(|seat| seat.player)
+            .collect()
+    }
+
+    fn seat_at(&self, address: ClientAddress) -> Option<&Seat> {
+        self.seats.iter().find(|seat| seat.address == address)
+    }
+
+    fn player_at(&self, address: ClientAddress) -> Option<Identity> {
+        self.seat_at(address).map(|seat| seat.player)
+    }
+
+    fn is_spectator(&self, address: ClientAddress) -> bool {
+        self.spectators
+            .iter()
+            .any(|spectator| spectator.address == address)
+    }
+
+    fn hear_from(&mut self, address: ClientAddress, now: f32) {
+        for seat in self.seats.iter_mut() {
+            if seat.address == address {
+                seat.last_heard = now;
+            }
+        }
+        for spectator in self.spectators.iter_mut() {
+            if spectator.address == address {
+                spectator.last_heard = now;
+            }
+        }
+    }
+
+    // gives the client whichever place in the match is still free, if any
+    fn admit(&mut self, address: ClientAddress, now: f32) -> Option<&Seat> {
+        if !self.reserved.is_empty() {
+            return None;
+        }
+        let player = [Identity::One, Identity::Two]
+            .into_iter()
+            .find(|player| self.seats.iter().all(|seat| seat.player != *player))?;
+        // tokens only need to be hard to guess, and every RandomState is seeded differently
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u32(now.to_bits());
+        self.seats.push(Seat {
+            address,
+            player,
+            token: hasher.finish(),
+            last_heard: now,
+        });
+        self.seats.last()
+    }
+
+    // gives a returning client its place back, at whatever address it has come back from,
+    // or a client arriving with a reserved token the place that was kept for it
+    fn reclaim(&mut self, address: ClientAddress, token: u64, now: f32) -> Option<&Seat> {
+        if let Some(index) = self.seats.iter().position(|seat| seat.token == token) {
+            let seat = &mut self.seats[index];
+            seat.address = address;
+            seat.last_heard = now;
+            return Some(seat);
+        }
+        let (player, token) = self.reserved.iter().copied().find(|(player, reserved)| {
+            *reserved == token && self.seats.iter().all(|seat| seat.player != *player)
+        })?;
+        self.seats.push(Seat {
+            address,
+            player,
+            token,
+            last_heard: now,
+        });
+        self.seats.last()
+    }
+}
+
+fn welcome(seat: Option<&Seat>) -> ServerMessage {
+    match seat {
+        Some(seat) => ServerMessage::Welcome {
+            player: seat.player,
+            token: seat.token,
+        },
+        None => ServerMessage::Full,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn receive_client_messages(
+    mut server: ResMut<Server>,
+    mut actions: ResMut<Actions>,
+    screen: Res<State<ServerScreen>>,
+    phase: Res<State<GamePhase>>,
+    mut next_phase: ResMut<NextState<GamePhase>>,
+    pause: Res<State<MatchPause>>,
+    mut forfeit: ResMut<Forfeit>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    for (message, address) in server.transport.receive::<ClientMessage>() {
+        server.hear_from(address, now);
+        match message {
+            ClientMessage::Join if server.is_spectator(address) => {
+                server.transport.send(&ServerMessage::Spectating, address);
+            }
+            ClientMessage::Join => {
+                let reply = match server.seat_at(address) {
+                    Some(seat) => welcome(Some(seat)),
+                    None => welcome(server.admit(address, now)),
+                };
+                server.transport.send(&reply, address);
+            }
+            ClientMessage::Rejoin { token } => {
+                let reply = welcome(server.reclaim(address, token, now));
+                server.transport.send(&reply, address);
+            }
+            // a player can't also watch, so is sent back its place in the match instead
+            ClientMessage::Spectate => {
+                let reply = match server.seat_at(address) {
+                    Some(seat) => welcome(Some(seat)),
+                    None => {
+                        if !server.is_spectator(address) {
+                            server.spectators.push(Spectator {
+                                address,
+                                last_heard: now,
+                            });
+                        }
+                        ServerMessage::Spectating
+                    }
+                };
+                server.transport.send(&reply, address);
+            }
+            ClientMessage::KeepAlive => {}
+            ClientMessage::Actions(client_actions) => {
+                // actions sent while the match is paused would all land at once when it resumes
+                if *screen.get() != ServerScreen::Match || *pause.get() == MatchPause::Paused {
+                    continue;
+                }
+                // actions are tagged with whoever sent them, not whoever they claim to be from
+                let Some(player) = server.player_at(address) else { continue };
+                for action in client_actions {
+                    actions.push(player, action);
+                }
+            }
+            ClientMessage::Leave => {
+                // leaving a match that is still being played gives it up
+                if let Some(player) = server.player_at(address) {
+                    let playing =
+                        *screen.get() == ServerScreen::Match && *phase.get() != GamePhase::GameOver;
+                    if playing && forfeit.0.is_none() {
+                        forfeit.0 = Some(player);
+                        next_phase.set(GamePhase::GameOver);
+                    }
+                }
+                server.seats.retain(|seat| seat.address != address);
+                server
+                    .spectators
+                    .retain(|spectator| spectator.address != address);
+            }
+        }
+    }
+}
+
+// Pauses the match while a player is missing, and gives it to the other player if they
+// don't come back in time. The match starts once two players are in, and the server waits
+// for the next two once everyone has left. Spectators who go quiet are simply forgotten.
+#[allow(clippy::too_many_arguments)]
+fn watch_players(
+    mut server: ResMut<Server>,
+    screen: Res<State<ServerScreen>>,
+    mut next_screen: ResMut<NextState<ServerScreen>>,
+    phase: Res<State<GamePhase>>,
+    mut next_phase: ResMut<NextState<GamePhase>>,
+    pause: Res<State<MatchPause>>,
+    mut next_pause: ResMut<NextState<MatchPause>>,
+    mut forfeit: ResMut<Forfeit>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    server
+        .spectators
+        .retain(|spectator| now - spectator.last_heard <= Server::TIMEOUT);
+    let missing = server.missing_players(now);
+    let abandoned_after = Server::TIMEOUT + server.grace_period;
+    let in_play = *screen.get() == ServerScreen::Match && *phase.get() != GamePhase::GameOver;
+    if in_play {
+        let abandoned = server
+            .seats
+            .iter()
+            .find(|seat| now - seat.last_heard > abandoned_after)
+            .map(|seat| seat.player);
+        if let Some(player) = abandoned {
+            forfeit.0 = Some(player);
+            next_phase.set(GamePhase::GameOver);
+            server
+                .seats
+                .retain(|seat| now - seat.last_heard <= abandoned_after);
+        }
+    } else {
+        // before the match starts or after it ends, there is nothing to hold a place for
+        server
+            .seats
+            .retain(|seat| now - seat.last_heard <= Server::TIMEOUT);
+    }
+
+    let paused = in_play && !missing.is_empty() && forfeit.0.is_none();
+    match (*pause.get(), paused) {
+        (MatchPause::Running, true) => next_pause.set(MatchPause::Paused),
+        (MatchPause::Paused, false) => next_pause.set(MatchPause::Running),
+        _ => {}
+    }
+    match (screen.get(), server.seats.len()) {
+        (ServerScreen::Lobby, 2) => next_screen.set(ServerScreen::Match),
+        (ServerScreen::Match, 0) => next_screen.set(ServerScreen::Lobby),
+        _ => {}
+    }
+}
+
+// should be run OnEnter(ServerScreen::Match)
+fn spawn_players(mut commands: Commands) {
+    commands.spawn((Position::Batter, PlayerOne, Score(0)));
+    commands.spawn((Position::Fielder, PlayerTwo, Score(0)));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn send_snapshots(
+    mut server: ResMut<Server>,
+    player_query: Query<(&Position, &Score, Has<PlayerOne>), With<Score>>,
+    ball_query: Query<(Entity, &Ball, &GlobalTransform, &Velocity)>,
+    batter_query: Query<(Entity, &Bat, &Transform, &Velocity), With<Batter>>,
+    fielder_query: Query<(Entity, &Fielder, &Transform, &Velocity)>,
+    phase: Res<State<GamePhase>>,
+    pause: Res<State<MatchPause>>,
+    over: Res<Over>,
+    forfeit: Res<Forfeit>,
+    time: Res<Time>,
+) {
+    let snapshot = Snapshot {
+        time: time.elapsed_seconds(),
+        phase: *phase.get(),
+        players: player_query
+            .iter()
+            .map(|(position, score, is_player_one)| PlayerState {
+                player: Identity::of(is_player_one),
+                position: *position,
+                score: score.0,
+            })
+            .collect(),
+        // the ball being bowled is held by a fielder, so use where it is on the field
+        balls: ball_query
+            .iter()
+            .map(|(entity, ball, transform, velocity)| BallState {
+                kind: ball.kind,
+                body: BodyState::new(
+                    entity.to_bits(),
+                    &transform.compute_transform(),
+                    velocity.linvel,
+                    velocity.angvel,
+                ),
+            })
+            .collect(),
+        bat: batter_query
+            .get_single()
+            .ok()
+            .map(|(entity, bat, transform, velocity)| BatState {
+                bat: *bat,
+                body: BodyState::new(
+                    entity.to_bits(),
+                    transform,
+                    velocity.linvel,
+                    velocity.angvel,
+                ),
+            }),
+        fielders: fielder_query
+            .iter()
+            .map(|(entity, fielder, transform, velocity)| FielderState {
+                position: fielder.position,
+                ring: fielder.ring,
+                body: BodyState::new(
+                    entity.to_bits(),
+                    transform,
+                    velocity.linvel,
+                    velocity.angvel,
+                ),
+            })
+            .collect(),
+        over: over.clone(),
+        paused: *pause.get() == MatchPause::Paused,
+        forfeit: forfeit.0,
+    };
+    // every snapshot holds the whole match, down to each ball of the over so far,
+    // so a spectator arriving partway through is caught up by the first one it is sent
+    let message = ServerMessage::Snapshot(snapshot);
+    let server = &mut *server;
+    let players = server.seats.iter().map(|seat| &seat.address);
+    let spectators = server.spectators.iter().map(|spectator| &spectator.address);
+    for address in players.chain(spectators) {
+        server.transport.send(&message, *address);
+    }
+}
+
+// Runs the match for two clients, who send their actions and are sent back snapshots.
+// The server is the only place the match is played out, so clients can't disagree about it.
+pub struct ServerPlugin {
+    address: SocketAddr,
+    websocket_address: Option<SocketAddr>,
+    reserved: Vec<(Identity, u64)>,
+    grace_period: f32,
+}
+
+impl ServerPlugin {
+    // how long a missing player has to come back, unless told otherwise
+    const GRACE_PERIOD: f32 = 30.;
+
+    pub fn new(address: SocketAddr) -> Self {
+        ServerPlugin {
+            address,
+            websocket_address: None,
+            reserved: Vec::new(),
+            grace_period: Self::GRACE_PERIOD,
+        }
+    }
+
+    // Also lets clients connect over websockets at `address`, such as from a browser.
+    pub fn with_websocket(mut self, address: SocketAddr) -> Self {
+        self.websocket_address = Some(address);
+        self
+    }
+
+    // Only lets in the players given here, each of whom must rejoin with its token
+    // rather than join, such as the members of a lobby room who were handed them.
+    pub fn with_reserved_seats(mut self, seats: impl IntoIterator<Item = (Identity, u64)>) -> Self {
+        self.reserved = seats.into_iter().collect();
+        self
+    }
+
+    // How long a player who drops out of a match has to rejoin it, in seconds,
+    // before the match is awarded to the other player.
+    pub fn with_grace_period(mut self, seconds: f32) -> Self {
+        self.grace_period = seconds;
+        self
+    }
+}
+
+impl Plugin for ServerPlugin {
+    fn build(&self, app: &mut App) {
+        let transport = ServerTransport::bind(self.address, self.websocket_address)
+            .expect("failed to bind the server socket");
+        app.add_state::<ServerScreen>()
+            .configure_set(Update, ServerSet.run_if(in_state(ServerScreen::Match)))
+            .add_plugins(GameplayPlugin::new(ServerSet, ServerScreen::Match))
+            .init_resource::<Actions>()
+            .insert_resource(Server {
+                transport,
+                seats: Vec::new(),
+                spectators: Vec::new(),
+                reserved: self.reserved.clone(),
+                grace_period: self.grace_period,
+            })
+            .add_systems(PreUpdate, (receive_client_messages, watch_players).chain())
+            .add_systems(OnEnter(ServerScreen::Match), spawn_players)
+            .add_systems(
+                PostUpdate,
+                send_snapshots.run_if(in_state(ServerScreen::Match)),
+            );
+    }
+}
@@ -0,0 +1,123 @@
+use std::net::SocketAddr;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use cricket_pong_support::NetSocket;
+
+use crate::client::ServerAddress;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::websocket::{WsConnection, WsListener};
+
+#[cfg(target_arch = "wasm32")]
+use crate::browser::WsConnection;
+
+// A client's link to the server, over whichever transport the server address asked for.
+pub enum Connection {
+    Udp {
+        socket: NetSocket,
+        server: SocketAddr,
+    },
+    WebSocket(Box<WsConnection>),
+}
+
+impl Connection {
+    pub fn open(address: &ServerAddress) -> std::io::Result<Self> {
+        match address {
+            ServerAddress::Udp(server) => {
+                let local_address = SocketAddr::from(([0, 0, 0, 0], 0));
+                Ok(Connection::Udp {
+                    socket: NetSocket::bind(local_address)?,
+                    server: *server,
+                })
+            }
+            ServerAddress::WebSocket(url) => {
+                Ok(Connection::WebSocket(Box::new(WsConnection::connect(url)?)))
+            }
+        }
+    }
+
+    pub fn send<T: Serialize>(&mut self, message: &T) {
+        match self {
+            Connection::Udp { socket, server } => socket.send(message, *server),
+            Connection::WebSocket(connection) => connection.send(message),
+        }
+    }
+
+    // every message from the server since the last call
+    pub fn receive<T: DeserializeOwned>(&mut self) -> Vec<T> {
+        match self {
+            Connection::Udp { socket, server } => socket
+                .receive()
+                .into_iter()
+                .filter_map(|(message, address)| (address == *server).then_some(message))
+                .collect(),
+            Connection::WebSocket(connection) => connection.receive(),
+        }
+    }
+}
+
+// Where a client is connected to the server from.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClientAddress {
+    Udp(SocketAddr),
+    WebSocket(SocketAddr),
+}
+
+// Every way the server can be reached: always over UDP, and over websockets if asked to.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct ServerTransport {
+    udp: NetSocket,
+    websocket: Option<WsListener>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ServerTransport {
+    pub fn bind(
+        udp_address: SocketAddr,
+        websocket_address: Option<SocketAddr>,
+    ) -> std::io::Result<Self> {
+        Ok(ServerTransport {
+            udp: NetSocket::bind(udp_address)?,
+            websocket: websocket_address.map(WsListener::bind).transpose()?,
+        })
+    }
+
+    pub fn udp_addr(&self) -> std::io::Result<SocketAddr> {
+        self.udp.local_addr()
+    }
+
+    pub fn websocket_addr(&self) -> Option<std::io::Result<SocketAddr>> {
+        self.websocket.as_ref().map(WsListener::local_addr)
+    }
+
+    pub fn send<T: Serialize>(&mut self, message: &T, address: ClientAddress) {
+        match (address, &mut self.websocket) {
+            (ClientAddress::Udp(address), _) => self.udp.send(message, address),
+            (ClientAddress::WebSocket(address), Some(websocket)) => {
+                websocket.send(message, address)
+            }
+            (ClientAddress::WebSocket(_), None) => {}
+        }
+    }
+
+    // every message that has arrived since the last call, over either transport
+    pub fn receive<T: DeserializeOwned>(&mut self) -> Vec<(T, ClientAddress)> {
+        let mut messages: Vec<(T, ClientAddress)> = self
+            .udp
+            .receive()
+            .into_iter()
+            .map(|(message, address)| (message, ClientAddress::Udp(address)))
+            .collect();
+        if let Some(websocket) = &mut self.websocket {
+            messages.extend(
+                websocket
+                    .receive()
+                    .into_iter()
+                    .map(|(message, address)| (message, ClientAddress::WebSocket(address))),
+            );
+        }
+        messages
+    }
+}
@@ -0,0 +1,195 @@
+use std::{
+    io::ErrorKind,
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use tungstenite::{
+    handshake::{
+        client::{ClientHandshake, Response},
+        server::{NoCallback, ServerHandshake},
+        HandshakeError, MidHandshake,
+    },
+    Message, WebSocket,
+};
+
+enum WsState {
+    // the two ends are still agreeing to talk, which may take a few frames
+    ClientHandshake(MidHandshake<ClientHandshake<TcpStream>>),
+    ServerHandshake(MidHandshake<ServerHandshake<TcpStream, NoCallback>>),
+    Open(WebSocket<TcpStream>),
+    Closed,
+}
+
+impl WsState {
+    fn from_client(
+        result: Result<
+            (WebSocket<TcpStream>, Response),
+            HandshakeError<ClientHandshake<TcpStream>>,
+        >,
+    ) -> Self {
+        match result {
+            Ok((socket, _)) => WsState::Open(socket),
+            Err(HandshakeError::Interrupted(handshake)) => WsState::ClientHandshake(handshake),
+            Err(HandshakeError::Failure(_)) => WsState::Closed,
+        }
+    }
+
+    fn from_server(
+        result: Result<
+            WebSocket<TcpStream>,
+            HandshakeError<ServerHandshake<TcpStream, NoCallback>>,
+        >,
+    ) -> Self {
+        match result {
+            Ok(socket) => WsState::Open(socket),
+            Err(HandshakeError::Interrupted(handshake)) => WsState::ServerHandshake(handshake),
+            Err(HandshakeError::Failure(_)) => WsState::Closed,
+        }
+    }
+}
+
+// A non-blocking websocket that sends and receives whole messages, in the same format
+// as NetSocket does over UDP.
+pub struct WsConnection(WsState);
+
+impl WsConnection {
+    // how long a frame can be held up waiting for the server to answer at all
+    const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+
+    // starts connecting to a url like ws://127.0.0.1:7778
+    pub(crate) fn connect(url: &str) -> std::io::Result<Self> {
+        let host = url
+            .split_once("://")
+            .map_or(url, |(_, rest)| rest)
+            .split('/')
+            .next()
+            .unwrap_or_default();
+        let address = host
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| std::io::Error::new(ErrorKind::NotFound, "no address for the host"))?;
+        let stream = TcpStream::connect_timeout(&address, Self::CONNECT_TIMEOUT)?;
+        stream.set_nonblocking(true)?;
+        Ok(WsConnection(WsState::from_client(tungstenite::client(
+            url, stream,
+        ))))
+    }
+
+    fn accept(stream: TcpStream) -> std::io::Result<Self> {
+        stream.set_nonblocking(true)?;
+        Ok(WsConnection(WsState::from_server(tungstenite::accept(
+            stream,
+        ))))
+    }
+
+    // moves the handshake along, returning the websocket once it is open
+    fn poll(&mut self) -> Option<&mut WebSocket<TcpStream>> {
+        self.0 = match std::mem::replace(&mut self.0, WsState::Closed) {
+            WsState::ClientHandshake(handshake) => WsState::from_client(handshake.handshake()),
+            WsState::ServerHandshake(handshake) => WsState::from_server(handshake.handshake()),
+            state => state,
+        };
+        match &mut self.0 {
+            WsState::Open(socket) => Some(socket),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        matches!(self.0, WsState::Closed)
+    }
+
+    // anything sent before the connection is open is lost, which the protocol already allows for
+    pub(crate) fn send<T: Serialize>(&mut self, message: &T) {
+        let Some(socket) = self.poll() else { return };
+        let Ok(bytes) = bincode::serialize(message) else { return };
+        // a message that can't be written straight away is queued, and written on a later call
+        let _ = socket.send(Message::Binary(bytes));
+    }
+
+    // every message that has arrived since the last call, skipping any that can't be read
+    pub(crate) fn receive<T: DeserializeOwned>(&mut self) -> Vec<T> {
+        let Some(socket) = self.poll() else {
+            return Vec::new();
+        };
+        let _ = socket.flush();
+        let mut messages = Vec::new();
+        let mut closed = false;
+        loop {
+            match socket.read() {
+                Ok(Message::Binary(bytes)) => {
+                    if let Ok(message) = bincode::deserialize(&bytes) {
+                        messages.push(message);
+                    }
+                }
+                Ok(_) => continue,
+                Err(tungstenite::Error::Io(error)) if error.kind() == ErrorKind::WouldBlock => {
+                    break
+                }
+                Err(_) => {
+                    closed = true;
+                    break;
+                }
+            }
+        }
+        if closed {
+            self.0 = WsState::Closed;
+        }
+        messages
+    }
+}
+
+// Accepts websocket connections, and talks to each of them by the address they connected from.
+pub(crate) struct WsListener {
+    listener: TcpListener,
+    connections: Vec<(SocketAddr, WsConnection)>,
+}
+
+impl WsListener {
+    pub(crate) fn bind(address: SocketAddr) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        Ok(WsListener {
+            listener,
+            connections: Vec::new(),
+        })
+    }
+
+    pub(crate) fn local_addr(&self) -> std::io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    pub(crate) fn send<T: Serialize>(&mut self, message: &T, address: SocketAddr) {
+        if let Some((_, connection)) = self
+            .connections
+            .iter_mut()
+            .find(|(connected, _)| *connected == address)
+        {
+            connection.send(message);
+        }
+    }
+
+    // every message that has arrived since the last call, from any connection
+    pub(crate) fn receive<T: DeserializeOwned>(&mut self) -> Vec<(T, SocketAddr)> {
+        while let Ok((stream, address)) = self.listener.accept() {
+            if let Ok(connection) = WsConnection::accept(stream) {
+                self.connections.push((address, connection));
+            }
+        }
+        let mut messages = Vec::new();
+        for (address, connection) in self.connections.iter_mut() {
+            messages.extend(
+                connection
+                    .receive()
+                    .into_iter()
+                    .map(|message| (message, *address)),
+            );
+        }
+        self.connections
+            .retain(|(_, connection)| !connection.is_closed());
+        messages
+    }
+}
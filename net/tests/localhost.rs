@@ -0,0 +1,382 @@
+use std::{net::SocketAddr, time::Duration};
+
+use bevy::prelude::{App, Entity, State, States, With};
+
+use cricket_pong_game::{
+    actions::{Action, Actions, FielderAction},
+    base::{
+        ball::Ball,
+        batter::{Bat, BatKind},
+        fielder::Fielder,
+        BowlScore, Identity, Position, Remote,
+    },
+    Forfeit, GamePhase, MatchPause, Over,
+};
+use cricket_pong_net::{
+    headless_app, ClientPlugin, ClientRole, NetClient, Replica, Server, ServerAddress, ServerPlugin,
+};
+
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, States)]
+enum TestScreen {
+    #[default]
+    Online,
+}
+
+const FRAME: Duration = Duration::from_millis(5);
+const MAX_FRAMES: usize = 2000;
+
+fn server_app() -> App {
+    let localhost = SocketAddr::from(([127, 0, 0, 1], 0));
+    let mut app = headless_app();
+    app.add_plugins(ServerPlugin::new(localhost).with_websocket(localhost));
+    app
+}
+
+fn client_app(server: ServerAddress) -> App {
+    let mut app = headless_app();
+    app.add_state::<TestScreen>()
+        .insert_resource(server)
+        .add_plugins(ClientPlugin::new(TestScreen::Online));
+    app
+}
+
+fn phase(app: &App) -> GamePhase {
+    *app.world.resource::<State<GamePhase>>().get()
+}
+
+fn pause(app: &App) -> MatchPause {
+    *app.world.resource::<State<MatchPause>>().get()
+}
+
+fn count_replicas<T: bevy::prelude::Component>(app: &mut App) -> usize {
+    app.world
+        .query_filtered::<(), (With<Replica>, With<T>)>()
+        .iter(&app.world)
+        .count()
+}
+
+// steps every app until `done` is true, failing if that takes too long
+fn run_until(apps: &mut [&mut App], mut done: impl FnMut(&mut [&mut App]) -> bool) {
+    for _ in 0..MAX_FRAMES {
+        for app in apps.iter_mut() {
+            app.update();
+        }
+        if done(apps) {
+            return;
+        }
+        std::thread::sleep(FRAME);
+    }
+    panic!("the apps never reached the expected state");
+}
+
+#[test]
+fn two_clients_play_on_a_local_server() {
+    let mut server = server_app();
+    let address = ServerAddress::Udp(server.world.resource::<Server>().local_addr());
+    let mut client_one = client_app(address.clone());
+    let mut client_two = client_app(address);
+
+    // both clients are let in, as different players
+    run_until(
+        &mut [&mut server, &mut client_one, &mut client_two],
+        |apps| {
+            apps[1].world.resource::<NetClient>().player().is_some()
+                && apps[2].world.resource::<NetClient>().player().is_some()
+        },
+    );
+    assert_ne!(
+        client_one.world.resource::<NetClient>().player(),
+        client_two.world.resource::<NetClient>().player(),
+    );
+    assert_eq!(server.world.resource::<Server>().client_count(), 2);
+
+    // the match starts on the server, and both clients are shown it
+    run_until(
+        &mut [&mut server, &mut client_one, &mut client_two],
+        |apps| {
+            apps[1..].iter_mut().all(|client| {
+                phase(client) == GamePhase::Bowling
+                    && count_replicas::<Ball>(client) == 1
+                    && count_replicas::<Fielder>(client) > 0
+            })
+        },
+    );
+
+    // player one bats first, and the batter isn't allowed to bowl
+    let (batter, fielder) = match client_one.world.resource::<NetClient>().player() {
+        Some(Identity::One) => (&mut client_one, &mut client_two),
+        _ => (&mut client_two, &mut client_one),
+    };
+    let batting_player = batter.world.resource::<NetClient>().player().unwrap();
+    for _ in 0..50 {
+        batter
+            .world
+            .resource_mut::<Actions>()
+            .push(batting_player, Action::Fielder(FielderAction::Bowl));
+        for app in [&mut server, &mut *batter, &mut *fielder] {
+            app.update();
+        }
+        std::thread::sleep(FRAME);
+    }
+    assert_eq!(phase(&server), GamePhase::Bowling);
+
+    // but the fielder is
+    let fielding_player = fielder.world.resource::<NetClient>().player().unwrap();
+    run_until(&mut [&mut server, batter, fielder], |apps| {
+        apps[2]
+            .world
+            .resource_mut::<Actions>()
+            .push(fielding_player, Action::Fielder(FielderAction::Bowl));
+        apps[1..]
+            .iter()
+            .all(|client| phase(client) == GamePhase::Active)
+    });
+    assert_eq!(phase(&server), GamePhase::Active);
+}
+
+#[test]
+fn the_batters_pick_of_bat_is_shown_to_both_clients() {
+    let mut server = server_app();
+    let address = ServerAddress::Udp(server.world.resource::<Server>().local_addr());
+    let mut client_one = client_app(address.clone());
+    let mut client_two = client_app(address);
+    run_until(
+        &mut [&mut server, &mut client_one, &mut client_two],
+        |apps| {
+            apps[1..]
+                .iter_mut()
+                .all(|client| phase(client) == GamePhase::Bowling)
+        },
+    );
+
+    // whoever is batting picked a wide bat
+    let batting_player = server
+        .world
+        .query::<(Entity, &Position)>()
+        .iter(&server.world)
+        .find_map(|(entity, position)| (*position == Position::Batter).then_some(entity))
+        .expect("someone should be batting");
+    server
+        .world
+        .entity_mut(batting_player)
+        .insert(BatKind::Wide);
+    run_until(
+        &mut [&mut server, &mut client_one, &mut client_two],
+        |apps| {
+            apps[1..].iter_mut().all(|client| {
+                client
+                    .world
+                    .query_filtered::<&Bat, With<Replica>>()
+                    .iter(&client.world)
+                    .any(|bat| *bat == BatKind::Wide.bat())
+            })
+        },
+    );
+}
+
+// stands in for a browser, which can only connect over websockets
+#[test]
+fn websocket_and_udp_clients_play_together() {
+    let mut server = server_app();
+    let udp_address = server.world.resource::<Server>().local_addr();
+    let websocket_address = server
+        .world
+        .resource::<Server>()
+        .websocket_addr()
+        .expect("the server should be listening for websockets");
+    let mut udp_client = client_app(ServerAddress::Udp(udp_address));
+    let mut websocket_client = client_app(
+        format!("ws://{websocket_address}")
+            .parse()
+            .expect("a ws:// url should be a websocket address"),
+    );
+
+    run_until(
+        &mut [&mut server, &mut udp_client, &mut websocket_client],
+        |apps| {
+            apps[1].world.resource::<NetClient>().player().is_some()
+                && apps[2].world.resource::<NetClient>().player().is_some()
+        },
+    );
+    assert_eq!(server.world.resource::<Server>().client_count(), 2);
+
+    // the websocket client is sent the same snapshots as the UDP one
+    run_until(
+        &mut [&mut server, &mut udp_client, &mut websocket_client],
+        |apps| {
+            apps[1..].iter_mut().all(|client| {
+                phase(client) == GamePhase::Bowling && count_replicas::<Ball>(client) == 1
+            })
+        },
+    );
+
+    // and its actions reach the server
+    let websocket_player = websocket_client
+        .world
+        .resource::<NetClient>()
+        .player()
+        .unwrap();
+    let udp_player = udp_client.world.resource::<NetClient>().player().unwrap();
+    run_until(
+        &mut [&mut server, &mut udp_client, &mut websocket_client],
+        |apps| {
+            apps[1]
+                .world
+                .resource_mut::<Actions>()
+                .push(udp_player, Action::Fielder(FielderAction::Bowl));
+            apps[2]
+                .world
+                .resource_mut::<Actions>()
+                .push(websocket_player, Action::Fielder(FielderAction::Bowl));
+            phase(apps[0]) == GamePhase::Active
+        },
+    );
+}
+
+#[test]
+fn a_spectator_joining_mid_match_watches_without_playing() {
+    let mut server = server_app();
+    let address = ServerAddress::Udp(server.world.resource::<Server>().local_addr());
+    let mut client_one = client_app(address.clone());
+    let mut client_two = client_app(address.clone());
+
+    run_until(
+        &mut [&mut server, &mut client_one, &mut client_two],
+        |apps| phase(apps[0]) == GamePhase::Bowling,
+    );
+    // a few balls have already been bowled by the time the spectator arrives
+    for value in [1, 4, 2] {
+        server.world.resource_mut::<Over>().push(BowlScore {
+            scorer: Identity::One,
+            value,
+        });
+    }
+
+    let mut spectator = client_app(address);
+    spectator.insert_resource(ClientRole::Spectator);
+    run_until(
+        &mut [
+            &mut server,
+            &mut client_one,
+            &mut client_two,
+            &mut spectator,
+        ],
+        |apps| {
+            let over = apps[3].world.resource::<Over>();
+            over.len() == 3
+                && over.get(1).is_some_and(|score| score.value == 4)
+                && count_replicas::<Ball>(apps[3]) == 1
+        },
+    );
+    let client = spectator.world.resource::<NetClient>();
+    assert!(client.is_spectating());
+    assert_eq!(client.player(), None);
+    assert_eq!(server.world.resource::<Server>().client_count(), 2);
+    assert_eq!(server.world.resource::<Server>().spectator_count(), 1);
+    // neither player is controlled from the spectator's machine
+    let remote_players = spectator
+        .world
+        .query_filtered::<(), With<Remote>>()
+        .iter(&spectator.world)
+        .count();
+    assert_eq!(remote_players, 2);
+
+    // the spectator's actions go nowhere, even for the side that is due to bowl
+    for _ in 0..50 {
+        for player in [Identity::One, Identity::Two] {
+            spectator
+                .world
+                .resource_mut::<Actions>()
+                .push(player, Action::Fielder(FielderAction::Bowl));
+        }
+        for app in [
+            &mut server,
+            &mut client_one,
+            &mut client_two,
+            &mut spectator,
+        ] {
+            app.update();
+        }
+        std::thread::sleep(FRAME);
+    }
+    assert_eq!(phase(&server), GamePhase::Bowling);
+
+    // a spectator that goes away without a word stops being sent the match
+    drop(spectator);
+    run_until(
+        &mut [&mut server, &mut client_one, &mut client_two],
+        |apps| apps[0].world.resource::<Server>().spectator_count() == 0,
+    );
+    assert_eq!(server.world.resource::<Server>().client_count(), 2);
+}
+
+#[test]
+fn a_dropped_player_pauses_the_match_until_they_rejoin() {
+    let mut server = server_app();
+    let address = ServerAddress::Udp(server.world.resource::<Server>().local_addr());
+    let mut client_one = client_app(address.clone());
+    let mut client_two = client_app(address.clone());
+    run_until(
+        &mut [&mut server, &mut client_one, &mut client_two],
+        |apps| {
+            apps[1..]
+                .iter()
+                .all(|client| phase(client) == GamePhase::Bowling)
+        },
+    );
+    let dropped = client_two.world.resource::<NetClient>();
+    let (player, token) = (dropped.player(), dropped.session_token());
+
+    // the second player's machine goes away without a word
+    drop(client_two);
+    run_until(&mut [&mut server, &mut client_one], |apps| {
+        pause(apps[0]) == MatchPause::Paused && pause(apps[1]) == MatchPause::Paused
+    });
+    assert_eq!(phase(&server), GamePhase::Bowling);
+    // their place is held for them, so nobody else can take it
+    assert_eq!(server.world.resource::<Server>().client_count(), 2);
+
+    // and they come back from a fresh start, with the token they were given
+    let mut rejoined = client_app(address);
+    rejoined.insert_resource(token.expect("the player should have been given a token"));
+    run_until(&mut [&mut server, &mut client_one, &mut rejoined], |apps| {
+        apps[2].world.resource::<NetClient>().player() == player
+            && apps
+                .iter()
+                .all(|app| pause(app) == MatchPause::Running && phase(app) == GamePhase::Bowling)
+    });
+    assert_eq!(server.world.resource::<Server>().client_count(), 2);
+    assert_eq!(server.world.resource::<Forfeit>().0, None);
+}
+
+#[test]
+fn a_player_who_never_returns_forfeits_the_match() {
+    let localhost = SocketAddr::from(([127, 0, 0, 1], 0));
+    let mut server = headless_app();
+    server.add_plugins(ServerPlugin::new(localhost).with_grace_period(0.5));
+    let address = ServerAddress::Udp(server.world.resource::<Server>().local_addr());
+    let mut client_one = client_app(address.clone());
+    let mut client_two = client_app(address);
+    run_until(
+        &mut [&mut server, &mut client_one, &mut client_two],
+        |apps| {
+            apps[1..]
+                .iter()
+                .all(|client| phase(client) == GamePhase::Bowling)
+        },
+    );
+    let stayed = client_one.world.resource::<NetClient>().player();
+    let left = client_two.world.resource::<NetClient>().player();
+
+    drop(client_two);
+    run_until(&mut [&mut server, &mut client_one], |apps| {
+        apps.iter().all(|app| phase(app) == GamePhase::GameOver)
+    });
+    for app in [&server, &client_one] {
+        let forfeit = app.world.resource::<Forfeit>();
+        assert_eq!(forfeit.0, left);
+        assert_eq!(forfeit.winner(), stayed);
+        assert_eq!(pause(app), MatchPause::Running);
+    }
+    assert_eq!(server.world.resource::<Server>().client_count(), 1);
+}
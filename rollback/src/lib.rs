@@ -0,0 +1,110 @@
+use std::{net::SocketAddr, time::Duration};
+
+use bevy::{
+    ecs::schedule::ScheduleLabel,
+    prelude::{
+        apply_deferred, apply_state_transition, in_state, App, IntoSystemConfigs, OnEnter, OnExit,
+        Plugin, PostUpdate, Resource, States, SystemSet,
+    },
+    transform::TransformSystem,
+};
+
+use bevy_rapier2d::prelude::{PhysicsSet, RapierPhysicsPlugin};
+
+use cricket_pong_game::{actions::Actions, base::Identity, GamePhase, GameplayMarkerPlugin};
+
+mod peer;
+
+mod session;
+pub use session::SyncTestReport;
+
+mod snapshot;
+pub use snapshot::Rollback;
+
+// The schedule that plays one tick of a rolled back match.
+// Add the GameplayPlugin to it with `GameplayPlugin::in_schedule(GameplayStep)`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, ScheduleLabel)]
+pub struct GameplayStep;
+
+// Gives anything spawned since the last tick its physics before the match is saved,
+// so a snapshot never holds entities that rapier has yet to hear about.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, ScheduleLabel)]
+pub(crate) struct PhysicsSync;
+
+// Tells rapier where the bodies of a match that was just loaded are.
+// Otherwise a body that changed parents in the rollback, like a ball that was picked up
+// for the next delivery, is taken for one moved by hand and rounded on its way back into
+// rapier, and the match plays out a little differently from there.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, ScheduleLabel)]
+pub(crate) struct PhysicsWriteback;
+
+// how much time passes in each tick of the match, however fast the game is running
+pub const TICK: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+#[derive(Clone, Debug, Resource)]
+pub enum RollbackMode {
+    // Both players are on this machine, and every tick is rolled back and played again
+    // `check_distance` ticks later to make sure that it plays out the same way.
+    SyncTest {
+        check_distance: u32,
+    },
+    // Plays against another machine that is running the same match.
+    PeerToPeer {
+        local_player: Identity,
+        local_address: SocketAddr,
+        peer_address: SocketAddr,
+    },
+}
+
+// Plays the match in fixed ticks, where the actions taken each tick are the only thing
+// that has to be shared for two machines to play the same match.
+// The gameplay must be added with `GameplayPlugin::in_schedule(GameplayStep)`, under the
+// same set and screen that this plugin is given.
+pub struct RollbackPlugin<Set: SystemSet, Screen: States> {
+    set: Set,
+    screen: Screen,
+    mode: RollbackMode,
+}
+
+impl<Set: SystemSet, Screen: States> RollbackPlugin<Set, Screen> {
+    pub fn new(set: Set, screen: Screen, mode: RollbackMode) -> Self {
+        RollbackPlugin { set, screen, mode }
+    }
+}
+
+impl<Set: SystemSet + Copy, Screen: States + Copy> Plugin for RollbackPlugin<Set, Screen> {
+    fn build(&self, app: &mut App) {
+        assert!(GameplayMarkerPlugin::is_added(app));
+        app.init_resource::<Actions>()
+            .insert_resource(self.mode.clone())
+            // phase changes are made within the tick that asked for them, so that none are
+            // left for the rest of the frame to make at a time no tick can account for
+            .add_systems(
+                GameplayStep,
+                (
+                    apply_state_transition::<GamePhase>.before(PhysicsSet::SyncBackend),
+                    apply_state_transition::<GamePhase>.after(self.set),
+                ),
+            )
+            .add_systems(
+                PhysicsSync,
+                (
+                    RapierPhysicsPlugin::<()>::get_systems(PhysicsSet::SyncBackend),
+                    apply_deferred,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                PhysicsWriteback,
+                RapierPhysicsPlugin::<()>::get_systems(PhysicsSet::Writeback),
+            )
+            .add_systems(OnEnter(self.screen), session::start_session)
+            .add_systems(OnExit(self.screen), session::end_session)
+            .add_systems(
+                PostUpdate,
+                session::run_session
+                    .run_if(in_state(self.screen))
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
@@ -0,0 +1,242 @@
+use std::{collections::BTreeMap, net::SocketAddr, time::Duration};
+
+use bevy::prelude::{NextState, State, World};
+
+use serde::{Deserialize, Serialize};
+
+use cricket_pong_game::{actions::Action, base::Identity, Forfeit, GamePhase, MatchPause};
+use cricket_pong_support::NetSocket;
+
+use crate::session::{input_slot, RollbackSession, SessionKind, TickInputs};
+
+// how far ahead of the other player the match can be played on a guess
+const MAX_PREDICTION: u32 = 8;
+// how long the other player can go quiet before the match is paused to wait for them
+const TIMEOUT: Duration = Duration::from_secs(2);
+// how much longer they have to come back before they forfeit the match
+const GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+// Sent to the other player every frame.
+// Inputs are resent until they are acknowledged, so a lost message costs nothing
+// but the time until the next one arrives.
+#[derive(Serialize, Deserialize)]
+struct PeerMessage {
+    // the tick the first of `inputs` was played on
+    from_tick: u32,
+    inputs: Vec<Vec<Action>>,
+    // every input from before this tick has been received
+    ack: u32,
+}
+
+pub(crate) struct PeerSession {
+    socket: NetSocket,
+    peer: SocketAddr,
+    local_player: Identity,
+    // the match starts once the other player has been heard from
+    connected: bool,
+    // time since the other player was last heard from
+    since_heard: Duration,
+    // inputs from this machine that might still need to be played again or sent
+    local_inputs: BTreeMap<u32, Vec<Action>>,
+    // inputs from the other player, which are known for every tick before `confirmed`
+    remote_inputs: BTreeMap<u32, Vec<Action>>,
+    confirmed: u32,
+    // what was guessed for the other player on each tick that isn't confirmed yet
+    predicted: BTreeMap<u32, Vec<Action>>,
+    // the first tick that was played with a wrong guess, and has yet to be played again
+    mispredicted: Option<u32>,
+    // the other player has every input from before this tick
+    peer_ack: u32,
+}
+
+impl PeerSession {
+    pub(crate) fn new(
+        local_player: Identity,
+        local_address: SocketAddr,
+        peer_address: SocketAddr,
+    ) -> Self {
+        PeerSession {
+            socket: NetSocket::bind(local_address).expect("failed to bind the peer socket"),
+            peer: peer_address,
+            local_player,
+            connected: false,
+            since_heard: Duration::ZERO,
+            local_inputs: BTreeMap::new(),
+            remote_inputs: BTreeMap::new(),
+            confirmed: 0,
+            predicted: BTreeMap::new(),
+            mispredicted: None,
+            peer_ack: 0,
+        }
+    }
+
+    pub(crate) fn local_player(&self) -> Identity {
+        self.local_player
+    }
+
+    // The inputs to play `tick` with.
+    // Until the other player's inputs arrive, they are assumed to keep doing
+    // whatever they were last known to be doing.
+    fn inputs(&mut self, tick: u32) -> TickInputs {
+        let mut inputs = TickInputs::default();
+        inputs[input_slot(self.local_player)] =
+            self.local_inputs.get(&tick).cloned().unwrap_or_default();
+        inputs[input_slot(!self.local_player)] = match self.remote_inputs.get(&tick) {
+            Some(remote) => remote.clone(),
+            None => {
+                let guess = self
+                    .remote_inputs
+                    .values()
+                    .next_back()
+                    .cloned()
+                    .unwrap_or_default();
+                self.predicted.insert(tick, guess.clone());
+                guess
+            }
+        };
+        inputs
+    }
+
+    // takes in every new input from the other player, noting any tick that was played
+    // with a wrong guess
+    fn receive(&mut self) {
+        for (message, address) in self.socket.receive::<PeerMessage>() {
+            if address != self.peer {
+                continue;
+            }
+            self.connected = true;
+            self.since_heard = Duration::ZERO;
+            self.peer_ack = self.peer_ack.max(message.ack);
+            for (tick, input) in (message.from_tick..).zip(message.inputs) {
+                if tick != self.confirmed {
+                    continue;
+                }
+                if let Some(guess) = self.predicted.remove(&tick) {
+                    if guess != input {
+                        self.mispredicted =
+                            Some(self.mispredicted.map_or(tick, |first| first.min(tick)));
+                    }
+                }
+                self.remote_inputs.insert(tick, input);
+                self.confirmed += 1;
+            }
+        }
+    }
+
+    fn send(&self, tick: u32) {
+        let message = PeerMessage {
+            from_tick: self.peer_ack,
+            inputs: (self.peer_ack..tick)
+                .map(|tick| self.local_inputs.get(&tick).cloned().unwrap_or_default())
+                .collect(),
+            ack: self.confirmed,
+        };
+        self.socket.send(&message, self.peer);
+    }
+
+    // forgets the inputs that will never be needed again,
+    // keeping the last confirmed input from the other player to guess from
+    fn forget_before(&mut self, tick: u32) {
+        self.local_inputs = self.local_inputs.split_off(&tick.min(self.peer_ack));
+        self.predicted = self.predicted.split_off(&tick);
+        let last = tick.saturating_sub(1);
+        self.remote_inputs = self.remote_inputs.split_off(&last);
+    }
+}
+
+// Pauses the match while the other player is quiet, the same way a server waits for a
+// missing player, and has them forfeit it if they stay away for too long.
+// Returns whether the match has to be held where it is.
+fn watch_peer(peer: &PeerSession, world: &mut World) -> bool {
+    let in_play = *world.resource::<State<GamePhase>>().get() != GamePhase::GameOver;
+    if in_play
+        && world.resource::<Forfeit>().0.is_none()
+        && peer.since_heard > TIMEOUT + GRACE_PERIOD
+    {
+        world.resource_mut::<Forfeit>().0 = Some(!peer.local_player);
+        world
+            .resource_mut::<NextState<GamePhase>>()
+            .set(GamePhase::GameOver);
+    }
+    let forfeited = world.resource::<Forfeit>().0.is_some();
+    let paused = in_play && !forfeited && peer.since_heard > TIMEOUT;
+    let pause = *world.resource::<State<MatchPause>>().get();
+    match (pause, paused) {
+        (MatchPause::Running, true) => world
+            .resource_mut::<NextState<MatchPause>>()
+            .set(MatchPause::Paused),
+        (MatchPause::Paused, false) => world
+            .resource_mut::<NextState<MatchPause>>()
+            .set(MatchPause::Running),
+        _ => {}
+    }
+    // nothing is played until the match is actually running again,
+    // or the gameplay would sit out ticks that the other player plays
+    forfeited || paused || pause == MatchPause::Paused
+}
+
+fn peer(session: &mut RollbackSession) -> &mut PeerSession {
+    match &mut session.kind {
+        SessionKind::PeerToPeer(peer) => peer,
+        SessionKind::SyncTest(_) => unreachable!("the session should be peer to peer"),
+    }
+}
+
+// Plays a match against another machine, where each side only sends the other their inputs.
+// Ticks are played straight away on a guess of what the other player did, and played again
+// from the last good snapshot whenever a guess turns out to be wrong.
+pub(crate) fn run_peer_session(session: &mut RollbackSession, world: &mut World, delta: Duration) {
+    peer(session).since_heard += delta;
+    peer(session).receive();
+    if !peer(session).connected {
+        // keep saying hello until the other player is there to hear it
+        peer(session).send(0);
+        session.pending = TickInputs::default();
+        return;
+    }
+    if watch_peer(peer(session), world) {
+        // keep telling the other player what they have missed, in case they come back
+        let tick = session.tick;
+        peer(session).send(tick);
+        session.pending = TickInputs::default();
+        // the time spent waiting isn't played through once the match carries on
+        session.due_ticks(delta);
+        return;
+    }
+
+    let mispredicted = peer(session).mispredicted.take();
+    if let Some(from) = mispredicted.filter(|from| *from < session.tick) {
+        session.load(world, from);
+        for tick in from..session.tick {
+            let inputs = peer(session).inputs(tick);
+            session.save(world, tick);
+            session.step(world, tick, &inputs);
+        }
+    }
+
+    let ticks = session.due_ticks(delta);
+    let first_tick = session.tick;
+    for _ in 0..ticks {
+        let tick = session.tick;
+        if tick.saturating_sub(peer(session).confirmed) >= MAX_PREDICTION {
+            break;
+        }
+        // whatever was held during the frame is held for every tick it covers
+        let local_slot = input_slot(peer(session).local_player);
+        let local = session.pending[local_slot].clone();
+        peer(session).local_inputs.insert(tick, local);
+        let inputs = peer(session).inputs(tick);
+        session.save(world, tick);
+        session.step(world, tick, &inputs);
+        session.tick += 1;
+    }
+    if session.tick > first_tick {
+        session.pending = TickInputs::default();
+    }
+
+    let tick = session.tick;
+    peer(session).send(tick);
+    let confirmed = peer(session).confirmed;
+    peer(session).forget_before(confirmed);
+    session.forget_before(confirmed);
+}
@@ -0,0 +1,260 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use bevy::{
+    ecs::system::{IntoSystem, System},
+    prelude::{Mut, Resource, Time, World},
+    utils::Instant,
+};
+
+use bevy_rapier2d::{plugin::systems::sync_removals, prelude::RapierConfiguration};
+
+use cricket_pong_game::{
+    actions::{Action, Actions},
+    base::Identity,
+};
+
+use crate::{
+    peer::{run_peer_session, PeerSession},
+    snapshot::{checksum, Registry, WorldSnapshot},
+    GameplayStep, PhysicsSync, PhysicsWriteback, RollbackMode, TICK,
+};
+
+// the most ticks a slow frame will play to catch up, beyond which the match slows down
+const MAX_CATCH_UP: u32 = 8;
+
+// What each player did during one tick, player one first.
+pub(crate) type TickInputs = [Vec<Action>; 2];
+
+pub(crate) fn input_slot(player: Identity) -> usize {
+    match player {
+        Identity::One => 0,
+        Identity::Two => 1,
+    }
+}
+
+// the same action taken over several frames is only taken once per tick
+pub(crate) fn add_input(inputs: &mut Vec<Action>, action: Action) {
+    if !inputs.contains(&action) {
+        inputs.push(action);
+    }
+}
+
+// The results of a sync test so far.
+// Every tick checked has been rolled back and played again, and any tick that
+// played out differently the second time is a mismatch.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct SyncTestReport {
+    pub checked_ticks: u32,
+    pub mismatched_ticks: Vec<u32>,
+}
+
+pub(crate) struct SyncTest {
+    check_distance: u32,
+    inputs: BTreeMap<u32, TickInputs>,
+    checksums: BTreeMap<u32, u64>,
+}
+
+pub(crate) enum SessionKind {
+    SyncTest(SyncTest),
+    PeerToPeer(PeerSession),
+}
+
+// The state of a rolled back match that lives outside the match itself:
+// the snapshots it can return to, and what is known about each player's inputs.
+#[derive(Resource)]
+pub(crate) struct RollbackSession {
+    registry: Registry,
+    // rapier's own cleanup, run by hand before physics are restored
+    removals: Box<dyn System<In = (), Out = ()>>,
+    epoch: Instant,
+    // the next tick to be played
+    pub(crate) tick: u32,
+    // time that has passed but hasn't been played through yet
+    accumulated: Duration,
+    // actions taken on this machine since the last tick was played
+    pub(crate) pending: TickInputs,
+    // the match as it was at the start of each tick that might still be played again
+    snapshots: BTreeMap<u32, WorldSnapshot>,
+    pub(crate) kind: SessionKind,
+}
+
+impl RollbackSession {
+    pub(crate) fn new(world: &mut World, mode: &RollbackMode) -> Self {
+        let mut removals = Box::new(IntoSystem::into_system(sync_removals));
+        removals.initialize(world);
+        let kind = match mode {
+            RollbackMode::SyncTest { check_distance } => SessionKind::SyncTest(SyncTest {
+                check_distance: (*check_distance).max(1),
+                inputs: BTreeMap::new(),
+                checksums: BTreeMap::new(),
+            }),
+            RollbackMode::PeerToPeer {
+                local_player,
+                local_address,
+                peer_address,
+            } => SessionKind::PeerToPeer(PeerSession::new(
+                *local_player,
+                *local_address,
+                *peer_address,
+            )),
+        };
+        RollbackSession {
+            registry: Registry::default(),
+            removals,
+            epoch: Instant::now(),
+            tick: 0,
+            accumulated: Duration::ZERO,
+            pending: TickInputs::default(),
+            snapshots: BTreeMap::new(),
+            kind,
+        }
+    }
+
+    pub(crate) fn save(&mut self, world: &mut World, tick: u32) {
+        world.run_schedule(PhysicsSync);
+        self.snapshots
+            .insert(tick, WorldSnapshot::save(world, &self.registry));
+    }
+
+    pub(crate) fn load(&mut self, world: &mut World, tick: u32) {
+        let snapshot = self
+            .snapshots
+            .get(&tick)
+            .expect("a tick should only be played again if it was saved");
+        snapshot.load(world, &self.registry, self.removals.as_mut());
+        world.run_schedule(PhysicsWriteback);
+    }
+
+    // forgets every snapshot from before `tick`, which can no longer be returned to
+    pub(crate) fn forget_before(&mut self, tick: u32) {
+        self.snapshots = self.snapshots.split_off(&tick);
+    }
+
+    // Moves the match on by one tick, as if `inputs` were everything the players did during it.
+    // The match is given a clock of its own for the tick, so it plays out the same way
+    // however long the frame actually took.
+    pub(crate) fn step(&self, world: &mut World, tick: u32, inputs: &TickInputs) {
+        {
+            let mut actions = world.resource_mut::<Actions>();
+            actions.0.clear();
+            for (player, player_inputs) in [Identity::One, Identity::Two].into_iter().zip(inputs) {
+                for action in player_inputs {
+                    actions.push(player, *action);
+                }
+            }
+        }
+        let mut time = Time::new(self.epoch);
+        time.update_with_instant(self.epoch + TICK * tick);
+        time.update_with_instant(self.epoch + TICK * (tick + 1));
+        let frame_time = world.remove_resource::<Time>();
+        world.insert_resource(time);
+        world.run_schedule(GameplayStep);
+        // anything left over was for a phase that has already passed
+        world.resource_mut::<Actions>().0.clear();
+        match frame_time {
+            Some(frame_time) => world.insert_resource(frame_time),
+            None => {
+                world.remove_resource::<Time>();
+            }
+        }
+    }
+
+    // takes every action queued this frame, keeping the ones this machine is responsible for
+    fn collect_inputs(&mut self, world: &mut World) {
+        let local_player = match &self.kind {
+            SessionKind::SyncTest(_) => None,
+            SessionKind::PeerToPeer(peer) => Some(peer.local_player()),
+        };
+        for queued in world.resource_mut::<Actions>().0.drain(..) {
+            if local_player.is_none_or(|player| player == queued.player) {
+                add_input(&mut self.pending[input_slot(queued.player)], queued.action);
+            }
+        }
+    }
+
+    // how many ticks are due, given the time since the last frame
+    pub(crate) fn due_ticks(&mut self, delta: Duration) -> u32 {
+        self.accumulated = (self.accumulated + delta).min(TICK * MAX_CATCH_UP);
+        let mut ticks = 0;
+        while self.accumulated >= TICK {
+            self.accumulated -= TICK;
+            ticks += 1;
+        }
+        ticks
+    }
+
+    fn run_sync_test(&mut self, world: &mut World, ticks: u32) {
+        for _ in 0..ticks {
+            // whatever was held during the frame is held for every tick it covers
+            let inputs = self.pending.clone();
+            let tick = self.tick;
+            self.save(world, tick);
+            self.step(world, tick, &inputs);
+            self.tick += 1;
+            let SessionKind::SyncTest(test) = &mut self.kind else { return };
+            test.inputs.insert(tick, inputs);
+            test.checksums.insert(tick + 1, checksum(world));
+            if self.tick < test.check_distance {
+                continue;
+            }
+
+            // go back and play the last few ticks again, which should change nothing
+            let from = self.tick - test.check_distance;
+            self.load(world, from);
+            let mut mismatches = Vec::new();
+            for replayed in from..self.tick {
+                let SessionKind::SyncTest(test) = &self.kind else { return };
+                let inputs = test.inputs[&replayed].clone();
+                self.save(world, replayed);
+                self.step(world, replayed, &inputs);
+                let SessionKind::SyncTest(test) = &self.kind else { return };
+                if test.checksums.get(&(replayed + 1)) != Some(&checksum(world)) {
+                    mismatches.push(replayed);
+                }
+            }
+            let mut report = world.resource_mut::<SyncTestReport>();
+            report.checked_ticks += 1;
+            report.mismatched_ticks.extend(mismatches);
+
+            self.forget_before(from + 1);
+            let SessionKind::SyncTest(test) = &mut self.kind else { return };
+            test.inputs = test.inputs.split_off(&(from + 1));
+            test.checksums = test.checksums.split_off(&(from + 1));
+        }
+        if ticks > 0 {
+            self.pending = TickInputs::default();
+        }
+    }
+}
+
+// should be run OnEnter(Screen)
+pub(crate) fn start_session(world: &mut World) {
+    let mode = world.resource::<RollbackMode>().clone();
+    let session = RollbackSession::new(world, &mode);
+    // physics move on by exactly one tick every time the match is stepped
+    world.resource_mut::<RapierConfiguration>().timestep_mode =
+        bevy_rapier2d::prelude::TimestepMode::Fixed {
+            dt: TICK.as_secs_f32(),
+            substeps: 1,
+        };
+    world.insert_resource(session);
+    world.insert_resource(SyncTestReport::default());
+}
+
+// should be run OnExit(Screen)
+pub(crate) fn end_session(world: &mut World) {
+    world.remove_resource::<RollbackSession>();
+}
+
+pub(crate) fn run_session(world: &mut World) {
+    let delta = world.resource::<Time>().delta();
+    world.resource_scope(|world, mut session: Mut<RollbackSession>| {
+        session.collect_inputs(world);
+        if matches!(session.kind, SessionKind::SyncTest(_)) {
+            let ticks = session.due_ticks(delta);
+            session.run_sync_test(world, ticks);
+        } else {
+            run_peer_session(&mut session, world, delta);
+        }
+    });
+}
@@ -0,0 +1,445 @@
+use std::{
+    any::Any,
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+};
+
+use bevy::{
+    ecs::{
+        component::Tick,
+        system::System,
+        world::{EntityMut, EntityRef},
+    },
+    hierarchy::{despawn_with_children_recursive, BuildWorldChildren, Children, Parent},
+    prelude::{
+        Component, ComputedVisibility, DetectChangesMut, Entity, GlobalTransform, NextState, Or,
+        Resource, State, States, Transform, Visibility, With, Without, World,
+    },
+};
+
+use bevy_rapier2d::{
+    prelude::{
+        ActiveEvents, Collider, ColliderMassProperties, Damping, ExternalForce, ExternalImpulse,
+        RapierColliderHandle, RapierContext, RapierRigidBodyHandle, Restitution, RigidBody, Sensor,
+        Velocity,
+    },
+    rapier::prelude::{ColliderHandle, RigidBodyHandle},
+};
+
+use cricket_pong_game::{
+    base::{
+        ball::Ball,
+        batter::{Bat, Batter, Wicket},
+        fielder::{Boundary, Fielder, FielderRing, OutfieldRotation},
+        obstacle::Obstacle,
+        power_up::{PowerUp, PowerUpEffect},
+        PlayerOne, PlayerTwo, Position, Score, WindVane,
+    },
+    random::MatchRng,
+    BallCountdown, Conditions, ExtraBallTimer, Forfeit, GamePhase, GustClock, MatchPause, Over,
+    PendingDelivery, PowerUpTimer, ShotClock,
+};
+
+// Marks an entity whose state is saved and restored with the match.
+// Anything with physics, a player, or a part of the match the players can't see is marked
+// the first time the match is saved.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct Rollback;
+
+type Saved = Box<dyn Any + Send + Sync>;
+
+// How one kind of component is copied out of an entity and written back into it.
+pub(crate) struct ComponentRollback {
+    save: fn(&EntityRef) -> Option<Saved>,
+    load: fn(&mut EntityMut, Option<&Saved>, Tick),
+}
+
+impl ComponentRollback {
+    fn of<C: Component + Clone>() -> Self {
+        ComponentRollback {
+            save: |entity| {
+                entity
+                    .get::<C>()
+                    .map(|component| Box::new(component.clone()) as Saved)
+            },
+            load: |entity, saved, tick| match saved.and_then(|saved| saved.downcast_ref::<C>()) {
+                // writing over a component doesn't count as a change, since the physics
+                // it would otherwise be synced to are restored along with it, and nor does
+                // anything that changed it before it was saved, which rapier has already seen
+                Some(saved) => match entity.get_mut::<C>() {
+                    Some(mut component) => {
+                        *component.bypass_change_detection() = saved.clone();
+                        component.set_last_changed(tick);
+                    }
+                    None => {
+                        entity.insert(saved.clone());
+                    }
+                },
+                None => {
+                    entity.remove::<C>();
+                }
+            },
+        }
+    }
+}
+
+// How one resource is copied out of the world and written back into it.
+pub(crate) struct ResourceRollback {
+    save: fn(&World) -> Saved,
+    load: fn(&mut World, &Saved),
+}
+
+impl ResourceRollback {
+    fn of<R: Resource + Clone>() -> Self {
+        ResourceRollback {
+            save: |world| Box::new(world.resource::<R>().clone()),
+            load: |world, saved| {
+                if let Some(saved) = saved.downcast_ref::<R>() {
+                    *world.resource_mut::<R>() = saved.clone();
+                }
+            },
+        }
+    }
+
+    // the current state is restored without running any of its transitions,
+    // and without looking like it just changed to the systems that watch for that
+    fn state<S: States>() -> Self {
+        ResourceRollback {
+            save: |world| {
+                Box::new((
+                    world.resource::<State<S>>().get().clone(),
+                    world.resource::<NextState<S>>().0.clone(),
+                ))
+            },
+            load: |world, saved| {
+                let Some((state, next_state)) = saved.downcast_ref::<(S, Option<S>)>() else { return };
+                *world.resource_mut::<State<S>>().bypass_change_detection() =
+                    State::new(state.clone());
+                world.resource_mut::<NextState<S>>().0 = next_state.clone();
+            },
+        }
+    }
+}
+
+// Everything that makes up the state of a match.
+pub(crate) struct Registry {
+    components: Vec<ComponentRollback>,
+    resources: Vec<ResourceRollback>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Registry {
+            components: vec![
+                ComponentRollback::of::<Transform>(),
+                ComponentRollback::of::<GlobalTransform>(),
+                ComponentRollback::of::<Visibility>(),
+                ComponentRollback::of::<ComputedVisibility>(),
+                ComponentRollback::of::<RigidBody>(),
+                ComponentRollback::of::<Velocity>(),
+                ComponentRollback::of::<ExternalImpulse>(),
+                ComponentRollback::of::<ExternalForce>(),
+                ComponentRollback::of::<Damping>(),
+                ComponentRollback::of::<Collider>(),
+                ComponentRollback::of::<ColliderMassProperties>(),
+                ComponentRollback::of::<Restitution>(),
+                ComponentRollback::of::<Sensor>(),
+                ComponentRollback::of::<ActiveEvents>(),
+                ComponentRollback::of::<Ball>(),
+                ComponentRollback::of::<Batter>(),
+                ComponentRollback::of::<Bat>(),
+                ComponentRollback::of::<Wicket>(),
+                ComponentRollback::of::<Fielder>(),
+                ComponentRollback::of::<FielderRing>(),
+                ComponentRollback::of::<Boundary>(),
+                ComponentRollback::of::<Obstacle>(),
+                ComponentRollback::of::<PowerUp>(),
+                ComponentRollback::of::<PowerUpEffect>(),
+                ComponentRollback::of::<WindVane>(),
+                ComponentRollback::of::<Position>(),
+                ComponentRollback::of::<Score>(),
+                ComponentRollback::of::<PlayerOne>(),
+                ComponentRollback::of::<PlayerTwo>(),
+            ],
+            resources: vec![
+                ResourceRollback::state::<GamePhase>(),
+                ResourceRollback::state::<MatchPause>(),
+                ResourceRollback::of::<Forfeit>(),
+                ResourceRollback::of::<Over>(),
+                ResourceRollback::of::<MatchRng>(),
+                ResourceRollback::of::<Conditions>(),
+                ResourceRollback::of::<GustClock>(),
+                ResourceRollback::of::<ShotClock>(),
+                ResourceRollback::of::<BallCountdown>(),
+                ResourceRollback::of::<PowerUpTimer>(),
+                ResourceRollback::of::<PendingDelivery>(),
+                ResourceRollback::of::<ExtraBallTimer>(),
+                ResourceRollback::of::<OutfieldRotation>(),
+            ],
+        }
+    }
+}
+
+// marks anything that belongs to the match but hasn't been saved before
+fn track_entities(world: &mut World) {
+    let mut query = world.query_filtered::<Entity, (
+        Without<Rollback>,
+        Or<(
+            With<RigidBody>,
+            With<Collider>,
+            With<Score>,
+            With<FielderRing>,
+            With<WindVane>,
+            With<PowerUpEffect>,
+        )>,
+    )>();
+    let untracked: Vec<Entity> = query.iter(world).collect();
+    for entity in untracked {
+        world.entity_mut(entity).insert(Rollback);
+    }
+}
+
+struct EntitySnapshot {
+    entity: Entity,
+    parent: Option<Entity>,
+    // the physics this entity had been given, if any
+    body: Option<RigidBodyHandle>,
+    collider: Option<ColliderHandle>,
+    components: Vec<Option<Saved>>,
+}
+
+// The whole state of a match at the start of a tick.
+pub(crate) struct WorldSnapshot {
+    // when it was saved, which every system that has run since will have seen
+    saved_at: Tick,
+    entities: Vec<EntitySnapshot>,
+    resources: Vec<Saved>,
+    physics: Vec<u8>,
+}
+
+impl WorldSnapshot {
+    pub(crate) fn save(world: &mut World, registry: &Registry) -> Self {
+        track_entities(world);
+        let mut query = world.query_filtered::<(
+            Entity,
+            Option<&Parent>,
+            Option<&RapierRigidBodyHandle>,
+            Option<&RapierColliderHandle>,
+        ), With<Rollback>>();
+        let entities = query
+            .iter(world)
+            .map(|(entity, parent, body, collider)| {
+                let entity_ref = world.entity(entity);
+                EntitySnapshot {
+                    entity,
+                    parent: parent.map(Parent::get),
+                    body: body.map(|body| body.0),
+                    collider: collider.map(|collider| collider.0),
+                    components: registry
+                        .components
+                        .iter()
+                        .map(|component| (component.save)(&entity_ref))
+                        .collect(),
+                }
+            })
+            .collect();
+        WorldSnapshot {
+            saved_at: world.change_tick(),
+            entities,
+            resources: registry
+                .resources
+                .iter()
+                .map(|resource| (resource.save)(world))
+                .collect(),
+            physics: bincode::serialize(world.resource::<RapierContext>())
+                .expect("the physics world should always be serializable"),
+        }
+    }
+
+    // Puts the match back the way it was when this was saved.
+    // Entities that have been despawned since are spawned again, but as new entities,
+    // so anything holding on to the old ones should not expect to find them.
+    // `removals` should be rapier's `sync_removals`, which is run to forget about
+    // anything despawned before the physics world is swapped out from under it.
+    pub(crate) fn load(
+        &self,
+        world: &mut World,
+        registry: &Registry,
+        removals: &mut dyn System<In = (), Out = ()>,
+    ) {
+        let mut query = world.query_filtered::<(
+            Entity,
+            Option<&RapierRigidBodyHandle>,
+            Option<&RapierColliderHandle>,
+        ), With<Rollback>>();
+        let current: HashMap<Entity, (Option<RigidBodyHandle>, Option<ColliderHandle>)> = query
+            .iter(world)
+            .map(|(entity, body, collider)| {
+                (
+                    entity,
+                    (body.map(|body| body.0), collider.map(|collider| collider.0)),
+                )
+            })
+            .collect();
+        // anything still around is written over, but its physics are only kept if they are
+        // the same ones being restored, otherwise they are built again from its components
+        // just as they were the first time
+        let kept: HashSet<Entity> = self
+            .entities
+            .iter()
+            .filter(|saved| current.contains_key(&saved.entity))
+            .map(|saved| saved.entity)
+            .collect();
+        let same_physics: HashSet<Entity> = self
+            .entities
+            .iter()
+            .filter(|saved| current.get(&saved.entity) == Some(&(saved.body, saved.collider)))
+            .map(|saved| saved.entity)
+            .collect();
+        for entity in kept.difference(&same_physics) {
+            world
+                .entity_mut(*entity)
+                .remove::<(RapierRigidBodyHandle, RapierColliderHandle)>();
+        }
+
+        // everything else is despawned, taking care not to take any kept children with it
+        for entity in current.keys().filter(|entity| !kept.contains(entity)) {
+            if world.get_entity(*entity).is_none() {
+                continue;
+            }
+            let tracked_children: Vec<Entity> = world
+                .get::<Children>(*entity)
+                .map(|children| {
+                    children
+                        .iter()
+                        .copied()
+                        .filter(|child| world.get::<Rollback>(*child).is_some())
+                        .collect()
+                })
+                .unwrap_or_default();
+            world.entity_mut(*entity).remove_children(&tracked_children);
+            despawn_with_children_recursive(world, *entity);
+        }
+        removals.run((), world);
+        removals.apply_deferred(world);
+
+        let saved_physics: RapierContext = bincode::deserialize(&self.physics)
+            .expect("the physics world should always be deserializable");
+        {
+            let mut context = world.resource_mut::<RapierContext>();
+            // the rest of the context maps entities to their physics, which only holds
+            // for the kept entities, so only the physics world itself is swapped out
+            context.islands = saved_physics.islands;
+            context.broad_phase = saved_physics.broad_phase;
+            context.narrow_phase = saved_physics.narrow_phase;
+            context.bodies = saved_physics.bodies;
+            context.colliders = saved_physics.colliders;
+            context.impulse_joints = saved_physics.impulse_joints;
+            context.multibody_joints = saved_physics.multibody_joints;
+            context.ccd_solver = saved_physics.ccd_solver;
+            context.pipeline = saved_physics.pipeline;
+            context.query_pipeline = saved_physics.query_pipeline;
+            context.integration_parameters = saved_physics.integration_parameters;
+
+            // bodies and colliders of entities that are being rebuilt or spawned again
+            // are made anew, so the saved ones are removed rather than left behind as ghosts
+            let RapierContext {
+                islands,
+                bodies,
+                colliders,
+                impulse_joints,
+                multibody_joints,
+                ..
+            } = &mut *context;
+            let is_ghost =
+                |user_data: u128| !same_physics.contains(&Entity::from_bits(user_data as u64));
+            let ghost_bodies: Vec<RigidBodyHandle> = bodies
+                .iter()
+                .filter(|(_, body)| is_ghost(body.user_data))
+                .map(|(handle, _)| handle)
+                .collect();
+            for handle in ghost_bodies {
+                bodies.remove(
+                    handle,
+                    islands,
+                    colliders,
+                    impulse_joints,
+                    multibody_joints,
+                    true,
+                );
+            }
+            let ghost_colliders: Vec<ColliderHandle> = colliders
+                .iter()
+                .filter(|(_, collider)| is_ghost(collider.user_data))
+                .map(|(handle, _)| handle)
+                .collect();
+            for handle in ghost_colliders {
+                colliders.remove(handle, islands, bodies, false);
+            }
+        }
+
+        let mut entities: HashMap<Entity, Entity> =
+            kept.iter().map(|entity| (*entity, *entity)).collect();
+        for saved in self.entities.iter() {
+            if !kept.contains(&saved.entity) {
+                entities.insert(saved.entity, world.spawn(Rollback).id());
+            }
+        }
+        for saved in self.entities.iter() {
+            let mut entity = world.entity_mut(entities[&saved.entity]);
+            for (component, value) in registry.components.iter().zip(saved.components.iter()) {
+                (component.load)(&mut entity, value.as_ref(), self.saved_at);
+            }
+            let parent = saved
+                .parent
+                .and_then(|parent| entities.get(&parent))
+                .copied();
+            if parent != entity.get::<Parent>().map(Parent::get) {
+                match parent {
+                    Some(parent) => entity.set_parent(parent),
+                    None => entity.remove_parent(),
+                };
+            }
+        }
+        for (resource, value) in registry.resources.iter().zip(self.resources.iter()) {
+            (resource.load)(world, value);
+        }
+    }
+}
+
+// A summary of the match that two worlds in the same state will always agree on,
+// whatever entities they happen to be using for it.
+pub(crate) fn checksum(world: &mut World) -> u64 {
+    let mut query = world
+        .query_filtered::<(Option<&Transform>, Option<&Velocity>, Option<&Score>), With<Rollback>>(
+        );
+    // entities are summed rather than hashed in order, so the order they are found in
+    // doesn't matter
+    let mut total: u64 = 0;
+    for (transform, velocity, score) in query.iter(world) {
+        let mut hasher = DefaultHasher::new();
+        if let Some(transform) = transform {
+            for value in transform
+                .translation
+                .to_array()
+                .into_iter()
+                .chain(transform.rotation.to_array())
+            {
+                value.to_bits().hash(&mut hasher);
+            }
+        }
+        if let Some(velocity) = velocity {
+            for value in velocity.linvel.to_array() {
+                value.to_bits().hash(&mut hasher);
+            }
+            velocity.angvel.to_bits().hash(&mut hasher);
+        }
+        score.map(|score| score.0).hash(&mut hasher);
+        total = total.wrapping_add(hasher.finish());
+    }
+    let mut hasher = DefaultHasher::new();
+    total.hash(&mut hasher);
+    world.resource::<State<GamePhase>>().get().hash(&mut hasher);
+    world.resource::<Over>().len().hash(&mut hasher);
+    hasher.finish()
+}
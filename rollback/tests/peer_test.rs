@@ -0,0 +1,104 @@
+use std::net::{SocketAddr, UdpSocket};
+
+use bevy::{
+    prelude::{App, Commands, OnEnter, State, States, SystemSet},
+    time::TimeUpdateStrategy,
+};
+
+use cricket_pong_game::{
+    base::{Identity, PlayerOne, PlayerTwo, Position, Score},
+    Forfeit, GamePhase, GameplayPlugin, MatchPause,
+};
+use cricket_pong_rollback::{GameplayStep, RollbackMode, RollbackPlugin, TICK};
+use cricket_pong_support::headless_app;
+
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, States)]
+enum TestScreen {
+    #[default]
+    Match,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, SystemSet)]
+struct TestSet;
+
+// long enough for a missing player to use up their grace period
+const MAX_FRAMES: usize = 3000;
+
+fn spawn_players(mut commands: Commands) {
+    commands.spawn((Position::Batter, PlayerOne, Score(0)));
+    commands.spawn((Position::Fielder, PlayerTwo, Score(0)));
+}
+
+// an address on this machine that nothing is listening on
+fn free_address() -> SocketAddr {
+    UdpSocket::bind("127.0.0.1:0")
+        .and_then(|socket| socket.local_addr())
+        .expect("failed to find a free port")
+}
+
+fn peer_app(local_player: Identity, local_address: SocketAddr, peer_address: SocketAddr) -> App {
+    let mut app = headless_app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(TICK))
+        .add_state::<TestScreen>()
+        .add_plugins(GameplayPlugin::new(TestSet, TestScreen::Match).in_schedule(GameplayStep))
+        .add_plugins(RollbackPlugin::new(
+            TestSet,
+            TestScreen::Match,
+            RollbackMode::PeerToPeer {
+                local_player,
+                local_address,
+                peer_address,
+            },
+        ))
+        .add_systems(OnEnter(TestScreen::Match), spawn_players);
+    app
+}
+
+fn phase(app: &App) -> GamePhase {
+    *app.world.resource::<State<GamePhase>>().get()
+}
+
+fn pause(app: &App) -> MatchPause {
+    *app.world.resource::<State<MatchPause>>().get()
+}
+
+// updates every app until `done` is true
+fn run_until(apps: &mut [&mut App], done: impl Fn(&[&mut App]) -> bool) {
+    for _ in 0..MAX_FRAMES {
+        for app in apps.iter_mut() {
+            app.update();
+        }
+        if done(apps) {
+            return;
+        }
+    }
+    panic!("the match never reached the expected state");
+}
+
+#[test]
+fn a_peer_who_never_returns_forfeits_the_match() {
+    let (one_address, two_address) = (free_address(), free_address());
+    let mut one = peer_app(Identity::One, one_address, two_address);
+    let mut two = peer_app(Identity::Two, two_address, one_address);
+
+    run_until(&mut [&mut one, &mut two], |apps| {
+        apps.iter().all(|app| phase(app) == GamePhase::Bowling)
+    });
+    assert_eq!(pause(&one), MatchPause::Running);
+
+    // the match waits for a while for player two to come back
+    drop(two);
+    run_until(&mut [&mut one], |apps| pause(apps[0]) == MatchPause::Paused);
+    assert_eq!(phase(&one), GamePhase::Bowling);
+    assert_eq!(one.world.resource::<Forfeit>().0, None);
+
+    // but they don't, so player one wins
+    run_until(&mut [&mut one], |apps| {
+        phase(apps[0]) == GamePhase::GameOver
+    });
+    assert_eq!(
+        one.world.resource::<Forfeit>().winner(),
+        Some(Identity::One)
+    );
+    assert_eq!(pause(&one), MatchPause::Running);
+}
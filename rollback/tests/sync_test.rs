@@ -0,0 +1,130 @@
+use bevy::{
+    prelude::{App, Commands, OnEnter, State, States, SystemSet, Transform, With},
+    time::TimeUpdateStrategy,
+};
+
+use cricket_pong_game::{
+    actions::{Action, Actions, BatterAction, FielderAction},
+    base::{batter::Batter, Identity, PlayerOne, PlayerTwo, Position, Score},
+    GamePhase, GameplayPlugin,
+};
+use cricket_pong_rollback::{GameplayStep, RollbackMode, RollbackPlugin, SyncTestReport, TICK};
+use cricket_pong_support::headless_app;
+
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, States)]
+enum TestScreen {
+    #[default]
+    Match,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, SystemSet)]
+struct TestSet;
+
+const MAX_FRAMES: usize = 1000;
+
+fn spawn_players(mut commands: Commands) {
+    commands.spawn((Position::Batter, PlayerOne, Score(0)));
+    commands.spawn((Position::Fielder, PlayerTwo, Score(0)));
+}
+
+fn sync_test_app() -> App {
+    let mut app = headless_app();
+    // every frame is exactly one tick long, so the test plays out the same way every time
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(TICK))
+        .add_state::<TestScreen>()
+        .add_plugins(GameplayPlugin::new(TestSet, TestScreen::Match).in_schedule(GameplayStep))
+        .add_plugins(RollbackPlugin::new(
+            TestSet,
+            TestScreen::Match,
+            RollbackMode::SyncTest { check_distance: 4 },
+        ))
+        .add_systems(OnEnter(TestScreen::Match), spawn_players);
+    app
+}
+
+fn phase(app: &App) -> GamePhase {
+    *app.world.resource::<State<GamePhase>>().get()
+}
+
+// updates the app, with the players taking `actions` every frame, until `done` is true
+fn run_until(app: &mut App, actions: &[(Identity, Action)], done: impl Fn(&App) -> bool) {
+    for _ in 0..MAX_FRAMES {
+        let mut queue = app.world.resource_mut::<Actions>();
+        for (player, action) in actions {
+            queue.push(*player, *action);
+        }
+        app.update();
+        if done(app) {
+            return;
+        }
+    }
+    panic!("the match never reached the expected state");
+}
+
+// where the bat is around the wicket, counter-clockwise from the right
+fn batter_angle(app: &mut App) -> f32 {
+    let translation = app
+        .world
+        .query_filtered::<&Transform, With<Batter>>()
+        .single(&app.world)
+        .translation;
+    translation.y.atan2(translation.x)
+}
+
+#[test]
+fn rolled_back_ticks_play_out_the_same() {
+    let mut app = sync_test_app();
+
+    run_until(&mut app, &[], |app| phase(app) == GamePhase::Bowling);
+    run_until(
+        &mut app,
+        &[
+            (Identity::Two, Action::Fielder(FielderAction::Bowl)),
+            (Identity::One, Action::Batter(BatterAction::MoveCW)),
+        ],
+        |app| phase(app) == GamePhase::Active,
+    );
+    // let the ball fly around for a while, with both players moving and the batter swinging
+    for frame in 0..240 {
+        let batter_action = if frame % 30 == 0 {
+            BatterAction::SwingCCW
+        } else {
+            BatterAction::MoveCCW
+        };
+        let mut actions = app.world.resource_mut::<Actions>();
+        actions.push(Identity::One, Action::Batter(batter_action));
+        actions.push(Identity::Two, Action::Fielder(FielderAction::MoveInfieldCW));
+        app.update();
+    }
+
+    let report = app.world.resource::<SyncTestReport>();
+    assert!(report.checked_ticks > 0);
+    assert!(
+        report.mismatched_ticks.is_empty(),
+        "ticks played out differently after a rollback: {:?}",
+        report.mismatched_ticks,
+    );
+}
+
+#[test]
+fn held_inputs_play_on_every_tick_of_a_slow_frame() {
+    let mut app = sync_test_app();
+    run_until(&mut app, &[], |app| phase(app) == GamePhase::Bowling);
+
+    // the next frame takes two ticks to play through, with the bat moving the whole time
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(TICK * 2));
+    let start = batter_angle(&mut app);
+    app.world
+        .resource_mut::<Actions>()
+        .push(Identity::One, Action::Batter(BatterAction::MoveCCW));
+    app.update();
+    // physics move first in each tick, so the last tick's movement shows in the one after it
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(TICK));
+    app.update();
+    let turned = batter_angle(&mut app) - start;
+    let per_tick = Batter::ROTATION_SPEED * TICK.as_secs_f32();
+    assert!(
+        (turned - per_tick * 2.).abs() < per_tick / 10.,
+        "the bat turned {turned}, rather than {per_tick} on each tick"
+    );
+}
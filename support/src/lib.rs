@@ -0,0 +1,25 @@
+use bevy::{
+    app::{PluginGroup, ScheduleRunnerPlugin},
+    asset::AddAsset,
+    prelude::{App, AssetPlugin, HierarchyPlugin, Mesh, MinimalPlugins, TransformPlugin},
+    scene::Scene,
+};
+
+mod socket;
+pub use socket::NetSocket;
+
+// An app without a window, for running a server or a client in tests.
+// Physics still expects meshes and scenes to be available to build colliders from.
+// There is no runner, so either add one or call `App::update` by hand.
+pub fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins.build().disable::<ScheduleRunnerPlugin>(),
+        TransformPlugin,
+        HierarchyPlugin,
+        AssetPlugin::default(),
+    ))
+    .add_asset::<Mesh>()
+    .add_asset::<Scene>();
+    app
+}
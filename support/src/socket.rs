@@ -0,0 +1,67 @@
+use std::{
+    io::ErrorKind,
+    net::{SocketAddr, UdpSocket},
+};
+
+use bevy::log::warn;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+// A non-blocking UDP socket that sends and receives whole messages.
+pub struct NetSocket(UdpSocket);
+
+impl NetSocket {
+    // the largest payload a single UDP datagram can carry
+    const MAX_PACKET_SIZE: usize = 65_507;
+
+    pub fn bind(address: SocketAddr) -> std::io::Result<Self> {
+        let socket = UdpSocket::bind(address)?;
+        socket.set_nonblocking(true)?;
+        Ok(NetSocket(socket))
+    }
+
+    pub fn local_addr(&self) -> std::io::Result<SocketAddr> {
+        self.0.local_addr()
+    }
+
+    pub fn send<T: Serialize>(&self, message: &T, address: SocketAddr) {
+        let Ok(bytes) = bincode::serialize(message) else { return };
+        // a message that can't be sent is as good as lost, which the protocol already allows for
+        let _ = self.0.send_to(&bytes, address);
+    }
+
+    // every message that has arrived since the last call, skipping any that can't be read
+    pub fn receive<T: DeserializeOwned>(&self) -> Vec<(T, SocketAddr)> {
+        let mut buffer = vec![0; Self::MAX_PACKET_SIZE];
+        let mut messages = Vec::new();
+        let mut retried = false;
+        loop {
+            match self.0.recv_from(&mut buffer) {
+                Ok((length, address)) => {
+                    if let Ok(message) = bincode::deserialize(&buffer[..length]) {
+                        messages.push((message, address));
+                    }
+                }
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                // errors from earlier sends (like an unreachable peer) are reported here,
+                // and there may still be messages waiting behind them
+                Err(error)
+                    if !retried
+                        && matches!(
+                            error.kind(),
+                            ErrorKind::ConnectionRefused | ErrorKind::ConnectionReset
+                        ) =>
+                {
+                    warn!("a message sent earlier didn't arrive: {error}");
+                    retried = true;
+                }
+                // anything else is left for the next call, rather than spinning on it now
+                Err(error) => {
+                    warn!("failed to receive messages: {error}");
+                    break;
+                }
+            }
+        }
+        messages
+    }
+}